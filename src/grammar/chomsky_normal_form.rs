@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use super::{grammar::Symbol, Grammar};
+
+impl Grammar {
+    pub fn to_chomsky_normal_form(&mut self) {
+        let start_idx = match self.start_symbol {
+            Some(idx) => idx,
+            None => return,
+        };
+
+        // START: the new start symbol never appears on a right side
+        let name = self.get_symbol_prime_name(self.get_symbol_name(start_idx).to_string());
+        let new_start_idx = self.add_non_terminal(&name);
+        self.add_production(new_start_idx, vec![start_idx]);
+        self.start_symbol = Some(new_start_idx);
+        // a cached nullable set would not know the new start symbol
+        self.invalidate_nullable_first_follow();
+
+        // DEL, UNIT
+        self.eliminate_epsilon_productions();
+        self.eliminate_unit_productions();
+        self.remove_useless_symbols();
+
+        // TERM: A -> ... a ... becomes A -> ... T_a ..., T_a -> a
+        let mut lifted: HashMap<usize, usize> = HashMap::new();
+        for i in 0..self.symbols.len() {
+            let productions = match self.symbols[i].non_terminal() {
+                Some(nt) => nt.productions.clone(),
+                None => continue,
+            };
+            let mut new_productions: Vec<Vec<usize>> = Vec::new();
+            for production in productions {
+                if production.len() < 2 {
+                    new_productions.push(production);
+                    continue;
+                }
+                let mut new_production: Vec<usize> = Vec::new();
+                for s in production {
+                    if let Symbol::Terminal(t) = &self.symbols[s] {
+                        let idx = match lifted.get(&s) {
+                            Some(&idx) => idx,
+                            None => {
+                                let name = self.get_symbol_prime_name(format!("T_{}", t));
+                                let idx = self.add_non_terminal(&name);
+                                self.add_production(idx, vec![s]);
+                                lifted.insert(s, idx);
+                                idx
+                            }
+                        };
+                        new_production.push(idx);
+                    } else {
+                        new_production.push(s);
+                    }
+                }
+                new_productions.push(new_production);
+            }
+            self.symbols[i].mut_non_terminal().unwrap().productions = new_productions;
+        }
+
+        // BIN: A -> X1 X2 ... Xn becomes A -> X1 A', A' -> X2 ... Xn
+        let mut tails: HashMap<Vec<usize>, usize> = HashMap::new();
+        let mut i = 0;
+        while i < self.symbols.len() {
+            let productions = match self.symbols[i].non_terminal() {
+                Some(nt) => nt.productions.clone(),
+                None => {
+                    i += 1;
+                    continue;
+                }
+            };
+            let mut new_productions: Vec<Vec<usize>> = Vec::new();
+            for production in productions {
+                if production.len() <= 2 {
+                    new_productions.push(production);
+                    continue;
+                }
+                let tail = production[1..].to_vec();
+                let idx = match tails.get(&tail) {
+                    Some(&idx) => idx,
+                    None => {
+                        let name = self.get_symbol_prime_name(self.get_symbol_name(i).to_string());
+                        let idx = self.add_non_terminal(&name);
+                        self.add_production(idx, tail.clone());
+                        tails.insert(tail, idx);
+                        idx
+                    }
+                };
+                new_productions.push(vec![production[0], idx]);
+            }
+            self.symbols[i].mut_non_terminal().unwrap().productions = new_productions;
+            i += 1;
+        }
+
+        self.invalidate_nullable_first_follow();
+        self.calculate_nullable_first_follow();
+    }
+}
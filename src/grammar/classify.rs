@@ -0,0 +1,97 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use super::{conflict::Conflict, lr_fsm::LRFSMType, Grammar};
+
+#[derive(Serialize)]
+pub struct GrammarClass {
+    pub name: &'static str,
+    pub member: bool,
+    pub first_conflict: Option<Conflict>,
+}
+
+#[derive(Serialize)]
+pub struct GrammarClassification {
+    pub classes: Vec<GrammarClass>,
+    #[serde(skip)]
+    terminals: Vec<String>,
+}
+
+impl GrammarClassification {
+    pub fn is(&self, name: &str) -> bool {
+        self.classes.iter().any(|c| c.name == name && c.member)
+    }
+
+    pub fn to_plaintext(&self) -> String {
+        self.classes
+            .iter()
+            .map(|c| match &c.first_conflict {
+                Some(conflict) => format!("{}: no, {}", c.name, conflict.to_plaintext()),
+                None => format!("{}: yes", c.name),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn to_latex(&self) -> String {
+        let terminal_set: HashSet<&str> = self.terminals.iter().map(|s| s.as_str()).collect();
+        let content = self
+            .classes
+            .iter()
+            .map(|c| match &c.first_conflict {
+                Some(conflict) => format!("{} & no & {}", c.name, conflict.to_latex(&terminal_set)),
+                None => format!("{} & yes & & & & ", c.name),
+            })
+            .collect::<Vec<_>>()
+            .join("\\\\\n");
+
+        "\\begin{tabular}{l|c|c|c|c|l}\n".to_string()
+            + "Class & Member & Location & Lookahead & Conflict & Actions\\\\\\hline\n"
+            + &content
+            + "\\\\\n\\end{tabular}"
+    }
+}
+
+impl Grammar {
+    pub fn classify(&mut self) -> Result<GrammarClassification, String> {
+        let mut classes: Vec<GrammarClass> = Vec::new();
+
+        let first_conflict = self
+            .generate_ll1_parsing_table()
+            .conflicts()
+            .conflicts
+            .into_iter()
+            .next();
+        classes.push(GrammarClass {
+            name: "LL(1)",
+            member: first_conflict.is_none(),
+            first_conflict,
+        });
+
+        for (t, name) in [
+            (LRFSMType::LR0, "LR(0)"),
+            (LRFSMType::SLR1, "SLR(1)"),
+            (LRFSMType::LALR, "LALR(1)"),
+            (LRFSMType::LR1, "LR(1)"),
+        ] {
+            let first_conflict = self
+                .to_lr_fsm(t)?
+                .to_parsing_table()
+                .conflicts()
+                .conflicts
+                .into_iter()
+                .next();
+            classes.push(GrammarClass {
+                name,
+                member: first_conflict.is_none(),
+                first_conflict,
+            });
+        }
+
+        Ok(GrammarClassification {
+            classes,
+            terminals: self.terminal_iter().cloned().collect(),
+        })
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::HashSet;
+
+use crowbook_text_processing::escape;
+use serde::Serialize;
+
+use super::{
+    ll1_parsing_table::LL1ParsingTable,
+    lr_fsm::{LRParsingTable, LRParsingTableAction},
+    pretty_print::production_right_to_latex,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum ConflictKind {
+    ShiftReduce,
+    ReduceReduce,
+    AcceptReduce,
+    FirstFirst,
+    FirstFollow,
+}
+
+impl ConflictKind {
+    pub fn to_plaintext(self) -> &'static str {
+        match self {
+            ConflictKind::ShiftReduce => "shift/reduce",
+            ConflictKind::ReduceReduce => "reduce/reduce",
+            ConflictKind::AcceptReduce => "accept/reduce",
+            ConflictKind::FirstFirst => "FIRST/FIRST",
+            ConflictKind::FirstFollow => "FIRST/FOLLOW",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum ConflictLocation {
+    State(usize),
+    NonTerminal(String),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Conflict {
+    pub location: ConflictLocation,
+    pub lookahead: String,
+    pub kind: ConflictKind,
+    pub productions: Vec<(String, Vec<String>)>,
+    pub shift: Option<usize>,
+    pub accept: bool,
+}
+
+impl Conflict {
+    pub fn to_plaintext(&self) -> String {
+        let location = match &self.location {
+            ConflictLocation::State(s) => format!("I{}", s),
+            ConflictLocation::NonTerminal(nt) => nt.clone(),
+        };
+        let actions = self
+            .shift
+            .iter()
+            .map(|s| format!("s{}", s))
+            .chain(self.accept.then(|| "acc".to_string()))
+            .chain(
+                self.productions
+                    .iter()
+                    .map(|(left, right)| format!("{} -> {}", left, right.join(" "))),
+            )
+            .collect::<Vec<_>>()
+            .join("; ");
+        format!(
+            "{} | {} | {} | {}",
+            location,
+            self.lookahead,
+            self.kind.to_plaintext(),
+            actions
+        )
+    }
+
+    pub fn to_latex(&self, terminal_set: &HashSet<&str>) -> String {
+        let location = match &self.location {
+            ConflictLocation::State(s) => format!("$I_{{{}}}$", s),
+            ConflictLocation::NonTerminal(nt) => format!("${}$", escape::tex(nt.as_str())),
+        };
+        let actions = self
+            .shift
+            .iter()
+            .map(|s| format!("shift {}", s))
+            .chain(self.accept.then(|| "accept".to_string()))
+            .chain(self.productions.iter().map(|(left, right)| {
+                format!(
+                    "${} \\rightarrow {}$",
+                    escape::tex(left.as_str()),
+                    production_right_to_latex(right.iter().map(|s| s.as_str()), terminal_set)
+                )
+            }))
+            .collect::<Vec<_>>()
+            .join("; ");
+        format!(
+            "{} & {} & {} & {}",
+            location,
+            escape::tex(self.lookahead.as_str()),
+            self.kind.to_plaintext(),
+            actions
+        )
+    }
+}
+
+#[derive(Serialize)]
+pub struct ConflictReport {
+    pub conflicts: Vec<Conflict>,
+    #[serde(skip)]
+    terminals: Vec<String>,
+}
+
+impl ConflictReport {
+    pub fn to_plaintext(&self) -> String {
+        if self.conflicts.is_empty() {
+            return "no conflicts".to_string();
+        }
+        self.conflicts
+            .iter()
+            .map(|c| c.to_plaintext())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn to_latex(&self) -> String {
+        let terminal_set: HashSet<&str> = self.terminals.iter().map(|s| s.as_str()).collect();
+        let content = self
+            .conflicts
+            .iter()
+            .map(|c| c.to_latex(&terminal_set) + "\\\\\n")
+            .collect::<String>();
+
+        "\\begin{tabular}{c|c|c|l}\n".to_string()
+            + "Location & Lookahead & Conflict & Actions\\\\\\hline\n"
+            + &content
+            + "\\end{tabular}"
+    }
+}
+
+impl LL1ParsingTable<'_> {
+    pub fn conflicts(&self) -> ConflictReport {
+        let mut conflicts: Vec<Conflict> = Vec::new();
+        for (left, row) in &self.rows {
+            for (terminal, cell) in self.terminals.iter().zip(row.iter()) {
+                if cell.rights.len() < 2 {
+                    continue;
+                }
+                // a nullable left side is predicted on every terminal of its FOLLOW
+                let kind = if self.nullable.contains(left)
+                    && self.follow.get(left).is_some_and(|f| f.contains(terminal))
+                {
+                    ConflictKind::FirstFollow
+                } else {
+                    ConflictKind::FirstFirst
+                };
+                conflicts.push(Conflict {
+                    location: ConflictLocation::NonTerminal(left.to_string()),
+                    lookahead: terminal.to_string(),
+                    kind,
+                    productions: cell
+                        .rights
+                        .iter()
+                        .map(|right| {
+                            (
+                                left.to_string(),
+                                right.iter().map(|s| s.to_string()).collect(),
+                            )
+                        })
+                        .collect(),
+                    shift: None,
+                    accept: false,
+                });
+            }
+        }
+
+        ConflictReport {
+            conflicts,
+            terminals: self.terminals.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+impl LRParsingTable {
+    pub fn conflicts(&self) -> ConflictReport {
+        let mut conflicts: Vec<Conflict> = Vec::new();
+        for (state, row) in self.action.iter().enumerate() {
+            for (terminal, actions) in self.terminals.iter().zip(row.iter()) {
+                if actions.len() < 2 {
+                    continue;
+                }
+                let mut shift: Option<usize> = None;
+                let mut accept = false;
+                let mut productions: Vec<(String, Vec<String>)> = Vec::new();
+                for action in actions {
+                    match action {
+                        LRParsingTableAction::Shift(s) => shift = Some(*s),
+                        LRParsingTableAction::Reduce(r) => productions.push(r.clone()),
+                        LRParsingTableAction::Accept => accept = true,
+                    }
+                }
+                conflicts.push(Conflict {
+                    location: ConflictLocation::State(state),
+                    lookahead: terminal.clone(),
+                    // $ is never shifted, so accept only meets reductions
+                    kind: if shift.is_some() {
+                        ConflictKind::ShiftReduce
+                    } else if accept {
+                        ConflictKind::AcceptReduce
+                    } else {
+                        ConflictKind::ReduceReduce
+                    },
+                    productions,
+                    shift,
+                    accept,
+                });
+            }
+        }
+
+        ConflictReport {
+            conflicts,
+            terminals: self.terminals.clone(),
+        }
+    }
+}
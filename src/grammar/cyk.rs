@@ -0,0 +1,166 @@
+use std::collections::BTreeSet;
+
+use crowbook_text_processing::escape;
+use serde::Serialize;
+
+use super::{pretty_print::table_to_plaintext, Grammar, EPSILON};
+
+#[derive(Serialize)]
+pub struct CYKTable {
+    pub tokens: Vec<String>,
+    // table[l - 1][i] holds the non-terminals deriving tokens[i..i + l]
+    pub table: Vec<Vec<Vec<String>>>,
+    pub start: String,
+    pub accepted: bool,
+}
+
+impl CYKTable {
+    pub fn to_plaintext(&self) -> String {
+        let n = self.tokens.len();
+        let cell = |set: &Vec<String>| {
+            if set.is_empty() {
+                "∅".to_string()
+            } else {
+                format!("{{{}}}", set.join(", "))
+            }
+        };
+
+        // the longest span on top, the tokens at the bottom
+        let mut rows: Vec<Vec<String>> = Vec::new();
+        for row in self.table.iter().rev() {
+            rows.push(row.iter().map(cell).collect());
+        }
+        rows.push(self.tokens.clone());
+
+        let mut output = if n == 0 {
+            String::new()
+        } else {
+            table_to_plaintext(&rows, &vec![false; n]) + "\n"
+        };
+        output += if self.accepted {
+            "accepted"
+        } else {
+            "rejected"
+        };
+        output + &format!(" (start symbol {})", self.start)
+    }
+
+    pub fn to_latex(&self) -> String {
+        let n = self.tokens.len();
+        let cell = |set: &Vec<String>| {
+            if set.is_empty() {
+                "$\\emptyset$".to_string()
+            } else {
+                format!(
+                    "$\\{{{}\\}}$",
+                    set.iter()
+                        .map(|s| escape::tex(s.as_str()))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            }
+        };
+
+        let mut rows: Vec<String> = Vec::new();
+        for (l, row) in self.table.iter().enumerate().rev() {
+            let line = if l + 1 == n {
+                "\\hline".to_string()
+            } else {
+                format!("\\cline{{1-{}}}", n - l)
+            };
+            rows.push(format!(
+                "{}\n{}\\\\",
+                line,
+                row.iter().map(cell).collect::<Vec<_>>().join(" & ")
+            ));
+        }
+        rows.push("\\hline".to_string());
+        rows.push(
+            self.tokens
+                .iter()
+                .map(|t| format!("\\multicolumn{{1}}{{c}}{{{}}}", escape::tex(t.as_str())))
+                .collect::<Vec<_>>()
+                .join(" & "),
+        );
+
+        format!(
+            "\\begin{{tabular}}{{{}}}\n{}\n\\end{{tabular}}",
+            "|c".repeat(n) + "|",
+            rows.join("\n")
+        )
+    }
+}
+
+impl Grammar {
+    // runs on the Chomsky normal form of a copy of the grammar
+    pub fn cyk(&self, tokens: &[&str]) -> Result<CYKTable, String> {
+        if self.start_symbol.is_none() {
+            return Err("start symbol is not set".to_string());
+        }
+
+        let mut g = self.clone();
+        g.to_chomsky_normal_form();
+        let start = g.start_symbol.unwrap();
+        let epsilon_idx = g.get_symbol_index(EPSILON).unwrap();
+
+        let n = tokens.len();
+        let mut table: Vec<Vec<BTreeSet<usize>>> =
+            (0..n).map(|l| vec![BTreeSet::new(); n - l]).collect();
+
+        for (i, token) in tokens.iter().enumerate() {
+            for nt in g.non_terminal_iter() {
+                if nt
+                    .productions
+                    .iter()
+                    .any(|p| p.len() == 1 && g.get_symbol_name(p[0]) == *token)
+                {
+                    table[0][i].insert(nt.index);
+                }
+            }
+        }
+
+        for l in 2..=n {
+            for i in 0..=n - l {
+                for k in 1..l {
+                    for nt in g.non_terminal_iter() {
+                        if nt.productions.iter().any(|p| {
+                            p.len() == 2
+                                && table[k - 1][i].contains(&p[0])
+                                && table[l - k - 1][i + k].contains(&p[1])
+                        }) {
+                            table[l - 1][i].insert(nt.index);
+                        }
+                    }
+                }
+            }
+        }
+
+        let accepted = if n == 0 {
+            g.symbols[start]
+                .non_terminal()
+                .unwrap()
+                .productions
+                .contains(&vec![epsilon_idx])
+        } else {
+            table[n - 1][0].contains(&start)
+        };
+
+        Ok(CYKTable {
+            tokens: tokens.iter().map(|s| s.to_string()).collect(),
+            table: table
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|set| {
+                            set.iter()
+                                .map(|&idx| g.get_symbol_name(idx).to_string())
+                                .collect()
+                        })
+                        .collect()
+                })
+                .collect(),
+            start: g.get_symbol_name(start).to_string(),
+            accepted,
+        })
+    }
+}
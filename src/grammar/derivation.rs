@@ -0,0 +1,197 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use super::{
+    ll1_parse::{LL1ParseAction, LL1ParseTrace},
+    lr_parse::{LRParseAction, LRParseTrace},
+    pretty_print::production_right_to_latex,
+    END_MARK, EPSILON,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum DerivationKind {
+    Leftmost,
+    Rightmost,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SententialForm {
+    pub symbols: Vec<String>,
+    // (position, length) of the handle, rightmost derivations only
+    pub handle: Option<(usize, usize)>,
+}
+
+#[derive(Serialize)]
+pub struct Derivation {
+    pub kind: DerivationKind,
+    pub forms: Vec<SententialForm>,
+    #[serde(skip)]
+    terminals: Vec<String>,
+}
+
+impl SententialForm {
+    fn to_plaintext(&self) -> String {
+        match self.handle {
+            Some((i, n)) => [
+                self.symbols[..i].join(" "),
+                if n == 0 {
+                    format!("[{}]", EPSILON)
+                } else {
+                    format!("[{}]", self.symbols[i..i + n].join(" "))
+                },
+                self.symbols[i + n..].join(" "),
+            ]
+            .into_iter()
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join(" "),
+            None if self.symbols.is_empty() => EPSILON.to_string(),
+            None => self.symbols.join(" "),
+        }
+    }
+
+    fn to_latex(&self, terminal_set: &HashSet<&str>) -> String {
+        let f = |symbols: &[String]| {
+            production_right_to_latex(symbols.iter().map(|s| s.as_str()), terminal_set)
+        };
+        match self.handle {
+            Some((i, n)) => {
+                let handle = if n == 0 {
+                    "\\underline{\\epsilon}".to_string()
+                } else {
+                    format!("\\underline{{{}}}", f(&self.symbols[i..i + n]))
+                };
+                [f(&self.symbols[..i]), handle, f(&self.symbols[i + n..])]
+                    .into_iter()
+                    .filter(|s| !s.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" \\  ")
+            }
+            None if self.symbols.is_empty() => "\\epsilon".to_string(),
+            None => f(&self.symbols),
+        }
+    }
+}
+
+impl Derivation {
+    pub fn to_plaintext(&self) -> String {
+        let arrow = match self.kind {
+            DerivationKind::Leftmost => "=>lm ",
+            DerivationKind::Rightmost => "=>rm ",
+        };
+        self.forms
+            .iter()
+            .enumerate()
+            .map(|(i, form)| {
+                let prefix = if i == 0 {
+                    " ".repeat(arrow.len())
+                } else {
+                    arrow.to_string()
+                };
+                prefix + &form.to_plaintext()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn to_latex(&self) -> String {
+        let terminal_set: HashSet<&str> = self.terminals.iter().map(|s| s.as_str()).collect();
+        let arrow = match self.kind {
+            DerivationKind::Leftmost => "\\Rightarrow_{lm}",
+            DerivationKind::Rightmost => "\\Rightarrow_{rm}",
+        };
+        let content = self
+            .forms
+            .iter()
+            .enumerate()
+            .map(|(i, form)| {
+                if i == 0 {
+                    form.to_latex(&terminal_set)
+                } else {
+                    format!("&{} {}", arrow, form.to_latex(&terminal_set))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\\\\\n");
+
+        "\\begin{align*}\n".to_string() + &content + "\n\\end{align*}"
+    }
+}
+
+impl LL1ParseTrace {
+    pub fn to_leftmost_derivation(&self) -> Result<Derivation, String> {
+        if !self.accepted {
+            return Err("input string is not accepted".to_string());
+        }
+
+        let is_non_terminal = |s: &String| s != EPSILON && !self.terminals.iter().any(|t| t == s);
+        let mut form: Vec<String> = self.steps[0]
+            .stack
+            .iter()
+            .filter(|s| *s != END_MARK)
+            .cloned()
+            .collect();
+        let mut forms = vec![SententialForm {
+            symbols: form.clone(),
+            handle: None,
+        }];
+        for step in &self.steps {
+            if let LL1ParseAction::Predict(_, right) = &step.action {
+                let i = form.iter().position(is_non_terminal).unwrap();
+                form.splice(i..i + 1, right.iter().filter(|s| *s != EPSILON).cloned());
+                forms.push(SententialForm {
+                    symbols: form.clone(),
+                    handle: None,
+                });
+            }
+        }
+
+        Ok(Derivation {
+            kind: DerivationKind::Leftmost,
+            forms,
+            terminals: self.terminals.clone(),
+        })
+    }
+}
+
+impl LRParseTrace {
+    // the reductions in reverse order
+    pub fn to_rightmost_derivation(&self) -> Result<Derivation, String> {
+        if !self.accepted {
+            return Err("input string is not accepted".to_string());
+        }
+
+        let mut forms: Vec<SententialForm> = Vec::new();
+        for step in &self.steps {
+            let symbols = || {
+                step.symbols[1..]
+                    .iter()
+                    .chain(step.input[..step.input.len() - 1].iter())
+                    .cloned()
+                    .collect::<Vec<_>>()
+            };
+            match &step.action {
+                LRParseAction::Reduce(_, right) => {
+                    let n = right.iter().filter(|s| *s != EPSILON).count();
+                    forms.push(SententialForm {
+                        symbols: symbols(),
+                        handle: Some((step.symbols.len() - 1 - n, n)),
+                    });
+                }
+                LRParseAction::Accept => forms.push(SententialForm {
+                    symbols: symbols(),
+                    handle: None,
+                }),
+                _ => {}
+            }
+        }
+        forms.reverse();
+
+        Ok(Derivation {
+            kind: DerivationKind::Rightmost,
+            forms,
+            terminals: self.terminals.clone(),
+        })
+    }
+}
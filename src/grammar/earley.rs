@@ -0,0 +1,406 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+};
+
+use serde::Serialize;
+
+use super::{
+    lr_fsm::DotProduction, parse_tree::ParseTree, pretty_print::production_right_to_latex, Grammar,
+    EPSILON,
+};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EarleyItem {
+    pub production: DotProduction,
+    pub origin: usize,
+}
+
+#[derive(Serialize)]
+pub struct EarleyParse {
+    pub tokens: Vec<String>,
+    pub accepted: bool,
+    pub chart: Vec<Vec<EarleyItem>>,
+    // at most the requested number of trees
+    pub trees: Vec<ParseTree>,
+    #[serde(skip)]
+    terminals: Vec<String>,
+}
+
+impl EarleyParse {
+    pub fn to_plaintext(&self) -> String {
+        let mut output: Vec<String> = Vec::new();
+        for (i, set) in self.chart.iter().enumerate() {
+            let items: Vec<String> = set
+                .iter()
+                .map(|item| item.production.to_plaintext())
+                .collect();
+            let width = items.iter().map(|s| s.chars().count()).max().unwrap_or(0);
+            let next = match self.tokens.get(i) {
+                Some(t) => format!(" (next: {})", t),
+                None => String::new(),
+            };
+            output.push(format!("S{}{}", i, next));
+            for (item, s) in set.iter().zip(items.iter()) {
+                let pad = width - s.chars().count();
+                output.push(format!("  {}{}  ({})", s, " ".repeat(pad), item.origin));
+            }
+        }
+
+        output.push(if self.accepted {
+            "accepted".to_string()
+        } else {
+            "rejected".to_string()
+        });
+        for (i, tree) in self.trees.iter().enumerate() {
+            output.push(format!("tree {}:", i + 1));
+            output.push(tree.to_plaintext());
+        }
+        output.join("\n")
+    }
+
+    pub fn to_latex(&self) -> String {
+        let terminal_set: HashSet<&str> = self.terminals.iter().map(|s| s.as_str()).collect();
+
+        let mut rows: Vec<String> = Vec::new();
+        for (i, set) in self.chart.iter().enumerate() {
+            let next = match self.tokens.get(i) {
+                Some(t) => format!(
+                    " ${}$",
+                    production_right_to_latex(std::iter::once(t.as_str()), &terminal_set)
+                ),
+                None => String::new(),
+            };
+            for (j, item) in set.iter().enumerate() {
+                let head = if j == 0 {
+                    format!("$S_{{{}}}${}", i, next)
+                } else {
+                    String::new()
+                };
+                let end = if j + 1 == set.len() {
+                    "\\\\\\hline"
+                } else {
+                    "\\\\"
+                };
+                rows.push(format!(
+                    "{} & {} & {}{}",
+                    head,
+                    item.production.to_latex(&terminal_set),
+                    item.origin,
+                    end
+                ));
+            }
+        }
+
+        let mut output = "\\begin{tabular}{l|l|c}\n".to_string()
+            + "set & item & origin\\\\\\hline\n"
+            + &rows.join("\n")
+            + "\n\\end{tabular}";
+        for tree in &self.trees {
+            output += "\n\n";
+            output += &tree.to_latex();
+        }
+        output
+    }
+}
+
+// (production, dot, start, end): right[dot..] of the production over tokens[start..end]
+type ItemSpan = (usize, usize, usize, usize);
+
+struct Forest<'a> {
+    g: &'a Grammar,
+    productions: &'a [(usize, Vec<usize>)],
+    // (non-terminal, start, end) -> productions completed over the span
+    completed: HashMap<(usize, usize, usize), Vec<usize>>,
+    tokens: &'a [&'a str],
+    limit: usize,
+    // results are only memoized when no cycle was cut while enumerating them
+    cuts: Cell<usize>,
+    tree_memo: RefCell<HashMap<(usize, usize, usize), Vec<ParseTree>>>,
+    sequence_memo: RefCell<HashMap<ItemSpan, Vec<Vec<ParseTree>>>>,
+    derive_memo: RefCell<HashMap<ItemSpan, bool>>,
+}
+
+impl Forest<'_> {
+    fn trees(
+        &self,
+        nt: usize,
+        start: usize,
+        end: usize,
+        path: &mut HashSet<(usize, usize, usize)>,
+    ) -> Vec<ParseTree> {
+        let key = (nt, start, end);
+        if let Some(trees) = self.tree_memo.borrow().get(&key) {
+            return trees.clone();
+        }
+        // a cycle (A =>+ A) would give infinitely many trees
+        if !path.insert(key) {
+            self.cuts.set(self.cuts.get() + 1);
+            return Vec::new();
+        }
+        let cuts = self.cuts.get();
+
+        let mut ret: Vec<ParseTree> = Vec::new();
+        'outer: for &p in self.completed.get(&key).into_iter().flatten() {
+            for children in self.sequences(p, 0, start, end, path) {
+                ret.push(ParseTree {
+                    symbol: self.g.get_symbol_name(nt).to_string(),
+                    children: if children.is_empty() {
+                        vec![ParseTree {
+                            symbol: EPSILON.to_string(),
+                            children: Vec::new(),
+                        }]
+                    } else {
+                        children
+                    },
+                });
+                if ret.len() >= self.limit {
+                    break 'outer;
+                }
+            }
+        }
+
+        path.remove(&key);
+        if self.cuts.get() == cuts {
+            self.tree_memo.borrow_mut().insert(key, ret.clone());
+        }
+        ret
+    }
+
+    // whether right[dot..] of production p can derive tokens[start..end]
+    fn derives(&self, p: usize, dot: usize, start: usize, end: usize) -> bool {
+        let key = (p, dot, start, end);
+        if let Some(&d) = self.derive_memo.borrow().get(&key) {
+            return d;
+        }
+        let d = match self.productions[p].1.get(dot) {
+            None => start == end,
+            Some(&s) if self.g.symbols[s].non_terminal().is_some() => (start..=end).any(|mid| {
+                self.completed.contains_key(&(s, start, mid)) && self.derives(p, dot + 1, mid, end)
+            }),
+            Some(&s) => {
+                start < end
+                    && self.tokens[start] == self.g.get_symbol_name(s)
+                    && self.derives(p, dot + 1, start + 1, end)
+            }
+        };
+        self.derive_memo.borrow_mut().insert(key, d);
+        d
+    }
+
+    // the ways for right[dot..] of production p to derive tokens[start..end]
+    fn sequences(
+        &self,
+        p: usize,
+        dot: usize,
+        start: usize,
+        end: usize,
+        path: &mut HashSet<(usize, usize, usize)>,
+    ) -> Vec<Vec<ParseTree>> {
+        let key = (p, dot, start, end);
+        if let Some(sequences) = self.sequence_memo.borrow().get(&key) {
+            return sequences.clone();
+        }
+        let s = match self.productions[p].1.get(dot) {
+            Some(&s) => s,
+            None if start == end => return vec![Vec::new()],
+            None => return Vec::new(),
+        };
+        let cuts = self.cuts.get();
+
+        let mut ret: Vec<Vec<ParseTree>> = Vec::new();
+        'outer: for mid in start..=end {
+            // only splits whose rest can still derive the remaining tokens
+            if !self.derives(p, dot + 1, mid, end) {
+                continue;
+            }
+            let heads: Vec<ParseTree> = if self.g.symbols[s].non_terminal().is_some() {
+                if !self.completed.contains_key(&(s, start, mid)) {
+                    continue;
+                }
+                self.trees(s, start, mid, path)
+            } else if mid == start + 1 && self.tokens[start] == self.g.get_symbol_name(s) {
+                vec![ParseTree {
+                    symbol: self.tokens[start].to_string(),
+                    children: Vec::new(),
+                }]
+            } else {
+                continue;
+            };
+            if heads.is_empty() {
+                continue;
+            }
+
+            let tails = self.sequences(p, dot + 1, mid, end, path);
+            for head in &heads {
+                for tail in &tails {
+                    ret.push(std::iter::once(head.clone()).chain(tail.clone()).collect());
+                    if ret.len() >= self.limit {
+                        break 'outer;
+                    }
+                }
+            }
+        }
+
+        if self.cuts.get() == cuts {
+            self.sequence_memo.borrow_mut().insert(key, ret.clone());
+        }
+        ret
+    }
+}
+
+impl Grammar {
+    pub fn earley_parse(&mut self, tokens: &[&str], limit: usize) -> Result<EarleyParse, String> {
+        let start = match self.start_symbol {
+            Some(idx) => idx,
+            None => return Err("start symbol is not set".to_string()),
+        };
+        if !self.is_nullable_first_follow_valid() {
+            self.calculate_nullable_first_follow();
+        }
+
+        let epsilon_idx = self.get_symbol_index(EPSILON).unwrap();
+        // ϵ is dropped from right sides, so A -> ϵ has an empty right side
+        let productions: Vec<(usize, Vec<usize>)> = self
+            .non_terminal_iter()
+            .flat_map(|nt| {
+                nt.productions.iter().map(|production| {
+                    (
+                        nt.index,
+                        production
+                            .iter()
+                            .cloned()
+                            .filter(|&s| s != epsilon_idx)
+                            .collect(),
+                    )
+                })
+            })
+            .collect();
+        let mut by_left: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (i, (left, _)) in productions.iter().enumerate() {
+            by_left.entry(*left).or_default().push(i);
+        }
+
+        // (production, dot, origin)
+        let mut chart: Vec<Vec<(usize, usize, usize)>> = vec![Vec::new(); tokens.len() + 1];
+        let mut seen: Vec<HashSet<(usize, usize, usize)>> = vec![HashSet::new(); tokens.len() + 1];
+        let add = |chart: &mut Vec<Vec<(usize, usize, usize)>>,
+                   seen: &mut Vec<HashSet<(usize, usize, usize)>>,
+                   i: usize,
+                   item: (usize, usize, usize)| {
+            if seen[i].insert(item) {
+                chart[i].push(item);
+            }
+        };
+
+        for &p in by_left.get(&start).into_iter().flatten() {
+            add(&mut chart, &mut seen, 0, (p, 0, 0));
+        }
+
+        for i in 0..=tokens.len() {
+            let mut j = 0;
+            while j < chart[i].len() {
+                let (p, dot, origin) = chart[i][j];
+                let (left, right) = &productions[p];
+                match right.get(dot) {
+                    Some(&s) => match self.symbols[s].non_terminal() {
+                        // predict
+                        Some(nt) => {
+                            for &q in by_left.get(&s).into_iter().flatten() {
+                                add(&mut chart, &mut seen, i, (q, 0, i));
+                            }
+                            // the nullable completion would not reach items added later
+                            if nt.nullable {
+                                add(&mut chart, &mut seen, i, (p, dot + 1, origin));
+                            }
+                        }
+                        // scan
+                        None => {
+                            if i < tokens.len() && tokens[i] == self.get_symbol_name(s) {
+                                add(&mut chart, &mut seen, i + 1, (p, dot + 1, origin));
+                            }
+                        }
+                    },
+                    // complete
+                    None => {
+                        let mut k = 0;
+                        while k < chart[origin].len() {
+                            let (q, d, o) = chart[origin][k];
+                            if productions[q].1.get(d) == Some(left) {
+                                add(&mut chart, &mut seen, i, (q, d + 1, o));
+                            }
+                            k += 1;
+                        }
+                    }
+                }
+                j += 1;
+            }
+        }
+
+        let mut completed: HashMap<(usize, usize, usize), Vec<usize>> = HashMap::new();
+        for (end, set) in chart.iter().enumerate() {
+            for &(p, dot, origin) in set {
+                if dot == productions[p].1.len() {
+                    completed
+                        .entry((productions[p].0, origin, end))
+                        .or_default()
+                        .push(p);
+                }
+            }
+        }
+        let accepted = completed.contains_key(&(start, 0, tokens.len()));
+
+        let trees = if accepted && limit > 0 {
+            let forest = Forest {
+                g: self,
+                productions: &productions,
+                completed,
+                tokens,
+                limit,
+                cuts: Cell::new(0),
+                tree_memo: RefCell::new(HashMap::new()),
+                sequence_memo: RefCell::new(HashMap::new()),
+                derive_memo: RefCell::new(HashMap::new()),
+            };
+            forest.trees(start, 0, tokens.len(), &mut HashSet::new())
+        } else {
+            Vec::new()
+        };
+
+        let chart = chart
+            .iter()
+            .map(|set| {
+                set.iter()
+                    .map(|&(p, dot, origin)| {
+                        let (left, right) = &productions[p];
+                        let production: Vec<String> = if right.is_empty() {
+                            vec![EPSILON.to_string()]
+                        } else {
+                            right
+                                .iter()
+                                .map(|&s| self.get_symbol_name(s).to_string())
+                                .collect()
+                        };
+                        EarleyItem {
+                            production: DotProduction {
+                                left: self.get_symbol_name(*left).to_string(),
+                                position: if right.is_empty() { 1 } else { dot },
+                                production,
+                                lookahead: None,
+                            },
+                            origin,
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Ok(EarleyParse {
+            tokens: tokens.iter().map(|s| s.to_string()).collect(),
+            accepted,
+            chart,
+            trees,
+            terminals: self.terminal_iter().cloned().collect(),
+        })
+    }
+}
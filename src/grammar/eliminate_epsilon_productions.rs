@@ -0,0 +1,100 @@
+use std::collections::HashSet;
+
+use super::{Grammar, EPSILON};
+
+impl Grammar {
+    pub fn eliminate_epsilon_productions(&mut self) {
+        if !self.is_nullable_first_follow_valid() {
+            self.calculate_nullable_first_follow();
+        }
+
+        let epsilon_idx = self.get_symbol_index(EPSILON).unwrap();
+        let nullable: Vec<bool> = self
+            .symbols
+            .iter()
+            .map(|s| s.non_terminal().is_some_and(|nt| nt.nullable))
+            .collect();
+
+        for nt in self.non_terminal_iter_mut() {
+            let nt_idx = nt.index;
+            let mut productions: Vec<Vec<usize>> = Vec::new();
+            for production in &nt.productions {
+                // every subset of the nullable symbols may be omitted, each occurrence
+                // is kept or dropped in turn and equal variants are merged right away
+                let mut variants: Vec<Vec<usize>> = vec![Vec::new()];
+                for &s in production.iter().filter(|&&s| s != epsilon_idx) {
+                    let kept: Vec<Vec<usize>> = variants
+                        .iter()
+                        .map(|v| v.iter().cloned().chain([s]).collect())
+                        .collect();
+                    if nullable[s] {
+                        let dropped = std::mem::take(&mut variants);
+                        for v in kept.into_iter().chain(dropped) {
+                            if !variants.contains(&v) {
+                                variants.push(v);
+                            }
+                        }
+                    } else {
+                        variants = kept;
+                    }
+                }
+
+                for new_production in variants {
+                    // A -> A derives nothing new
+                    if !new_production.is_empty()
+                        && new_production != [nt_idx]
+                        && !productions.contains(&new_production)
+                    {
+                        productions.push(new_production);
+                    }
+                }
+            }
+            nt.productions = productions;
+        }
+
+        // non-terminals deriving only ϵ are left without productions, drop every use of them
+        let mut removed: HashSet<usize> = HashSet::new();
+        loop {
+            let empty: HashSet<usize> = self
+                .non_terminal_iter()
+                .filter(|nt| nt.productions.is_empty() && !removed.contains(&nt.index))
+                .map(|nt| nt.index)
+                .collect();
+            if empty.is_empty() {
+                break;
+            }
+            for nt in self.non_terminal_iter_mut() {
+                nt.productions
+                    .retain(|production| production.iter().all(|s| !empty.contains(s)));
+            }
+            removed.extend(empty);
+        }
+
+        if let Some(start_idx) = self.start_symbol {
+            if nullable[start_idx] {
+                let start_on_right = self.non_terminal_iter().any(|nt| {
+                    nt.productions
+                        .iter()
+                        .any(|production| production.contains(&start_idx))
+                });
+
+                if start_on_right {
+                    let name =
+                        self.get_symbol_prime_name(self.get_symbol_name(start_idx).to_string());
+                    let new_start_idx = self.add_non_terminal(&name);
+                    self.add_production(new_start_idx, vec![start_idx]);
+                    self.add_production(new_start_idx, vec![epsilon_idx]);
+                    self.start_symbol = Some(new_start_idx);
+                } else {
+                    removed.remove(&start_idx);
+                    self.add_production(start_idx, vec![epsilon_idx]);
+                }
+            }
+        }
+
+        self.remove_symbols(&removed);
+
+        self.invalidate_nullable_first_follow();
+        self.calculate_nullable_first_follow();
+    }
+}
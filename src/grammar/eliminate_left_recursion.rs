@@ -1,13 +1,180 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use serde::Serialize;
 
 use super::{grammar::NonTerminal, Grammar, EPSILON};
 
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum LeftRecursionError {
+    // A =>+ α A β with α nullable but not empty, e.g. A -> B A a, B -> ϵ
+    HiddenLeftRecursion { derivation: Vec<String> },
+    // A =>+ A
+    Cycle { derivation: Vec<String> },
+}
+
+impl std::fmt::Display for LeftRecursionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LeftRecursionError::HiddenLeftRecursion { derivation } => {
+                write!(f, "hidden left recursion: {}", derivation.join(" =>* "))
+            }
+            LeftRecursionError::Cycle { derivation } => {
+                write!(f, "cycle: {}", derivation.join(" =>* "))
+            }
+        }
+    }
+}
+
+// X -> α C β where α is nullable
+struct LeftCornerEdge {
+    from: usize,
+    to: usize,
+    production: Vec<usize>,
+    position: usize,
+    hidden: bool,
+    cycle: bool,
+}
+
 impl Grammar {
-    pub fn eliminate_left_recursion(&mut self) {
+    fn left_corner_edges(&self) -> Vec<LeftCornerEdge> {
+        let epsilon_idx = self.get_symbol_index(EPSILON).unwrap();
+        let nullable = |s: usize| self.symbols[s].non_terminal().is_some_and(|nt| nt.nullable);
+
+        let mut edges: Vec<LeftCornerEdge> = Vec::new();
+        for nt in self.non_terminal_iter() {
+            for production in &nt.productions {
+                for (position, &s) in production.iter().enumerate() {
+                    if s != epsilon_idx && self.symbols[s].non_terminal().is_some() {
+                        edges.push(LeftCornerEdge {
+                            from: nt.index,
+                            to: s,
+                            production: production.clone(),
+                            position,
+                            hidden: production[..position].iter().any(|&s| s != epsilon_idx),
+                            cycle: production[position + 1..].iter().all(|&s| nullable(s)),
+                        });
+                    }
+                    if !nullable(s) {
+                        break;
+                    }
+                }
+            }
+        }
+        edges
+    }
+
+    fn left_corner_derivation(&self, path: &[&LeftCornerEdge], cycle: bool) -> Vec<String> {
+        let mut form: Vec<usize> = vec![path[0].from];
+        let mut derivation: Vec<String> = Vec::new();
+        let mut push = |form: &Vec<usize>| {
+            derivation.push(self.production_to_vec_str(form).join(" "));
+        };
+        push(&form);
+        for edge in path {
+            form = edge
+                .production
+                .iter()
+                .chain(form[1..].iter())
+                .cloned()
+                .collect();
+            push(&form);
+            let end = if cycle {
+                edge.production.len()
+            } else {
+                edge.position + 1
+            };
+            if edge.position > 0 || end > edge.position + 1 {
+                form = std::iter::once(edge.to)
+                    .chain(form[end..].iter().cloned())
+                    .collect();
+                push(&form);
+            }
+        }
+        derivation
+    }
+
+    // finds a path from `from` to `to` using edges satisfying `filter`
+    fn find_left_corner_path<'a>(
+        &self,
+        edges: &'a [LeftCornerEdge],
+        from: usize,
+        to: usize,
+        filter: impl Fn(&LeftCornerEdge) -> bool,
+    ) -> Option<Vec<&'a LeftCornerEdge>> {
+        let mut parent: HashMap<usize, &LeftCornerEdge> = HashMap::new();
+        let mut q: VecDeque<usize> = VecDeque::new();
+        q.push_back(from);
+        while let Some(u) = q.pop_front() {
+            for edge in edges.iter().filter(|e| e.from == u && filter(e)) {
+                if edge.to == to {
+                    let mut path = vec![edge];
+                    let mut v = u;
+                    while v != from {
+                        let e = parent[&v];
+                        path.push(e);
+                        v = e.from;
+                    }
+                    path.reverse();
+                    return Some(path);
+                }
+                if edge.to != from && !parent.contains_key(&edge.to) {
+                    parent.insert(edge.to, edge);
+                    q.push_back(edge.to);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn check_left_recursion(&mut self) -> Result<(), LeftRecursionError> {
         if !self.is_nullable_first_follow_valid() {
             self.calculate_nullable_first_follow();
         }
 
+        let edges = self.left_corner_edges();
+
+        for nt in self.non_terminal_iter() {
+            if let Some(path) = self.find_left_corner_path(&edges, nt.index, nt.index, |e| e.cycle)
+            {
+                return Err(LeftRecursionError::Cycle {
+                    derivation: self.left_corner_derivation(&path, true),
+                });
+            }
+        }
+
+        for edge in edges.iter().filter(|e| e.hidden) {
+            let path = if edge.to == edge.from {
+                Some(vec![edge])
+            } else {
+                self.find_left_corner_path(&edges, edge.to, edge.from, |_| true)
+                    .map(|path| std::iter::once(edge).chain(path).collect())
+            };
+            if let Some(path) = path {
+                return Err(LeftRecursionError::HiddenLeftRecursion {
+                    derivation: self.left_corner_derivation(&path, false),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn eliminate_left_recursion(&mut self) -> Result<(), LeftRecursionError> {
+        self.substitute_and_eliminate_left_recursion(false)
+    }
+
+    // Orders non-terminals as A_1, ..., A_n and substitutes A_i -> A_j γ (j < i)
+    // until every production of A_i starts with a terminal or A_j (j > i),
+    // then removes the immediate left recursion of A_i.
+    //
+    // With `epsilon_free`, A -> A α | β becomes A -> β | β A', A' -> α | α A'
+    // instead of A -> β A', A' -> α A' | ϵ.
+    pub(super) fn substitute_and_eliminate_left_recursion(
+        &mut self,
+        epsilon_free: bool,
+    ) -> Result<(), LeftRecursionError> {
+        self.check_left_recursion()?;
+
         let epsilon_idx = self.get_symbol_index(EPSILON).unwrap();
         let offset = self.symbols.len();
 
@@ -28,22 +195,30 @@ impl Grammar {
             let (nt, _) = b.split_first_mut().unwrap();
             let replace = &replace[..];
 
-            let old_productions = std::mem::replace(&mut nt.productions, Vec::new());
+            // substituted productions are examined again, in their original order;
+            // this terminates because check_left_recursion has ruled out cycles and
+            // hidden left recursion, so no leading non-terminal can reappear forever
+            let mut stack: Vec<Vec<usize>> = std::mem::take(&mut nt.productions);
+            stack.reverse();
             let mut recursive_productions: Vec<Vec<usize>> = Vec::new();
-            for mut production in old_productions {
+            while let Some(mut production) = stack.pop() {
                 if let Some(idx) = production.first() {
                     if let Some(&arr_idx) = map.get(idx) {
                         match arr_idx.cmp(&i) {
                             std::cmp::Ordering::Less => {
-                                for prefix in &replace[arr_idx].productions {
-                                    let new_production =
-                                        prefix.iter().chain(production.iter().skip(1)).cloned();
-
-                                    if Some(&nt.index) == prefix.first() {
-                                        recursive_productions.push(new_production.skip(1).collect())
+                                for prefix in replace[arr_idx].productions.iter().rev() {
+                                    // ϵ disappears unless it is the whole production
+                                    let new_production: Vec<usize> = prefix
+                                        .iter()
+                                        .chain(production.iter().skip(1))
+                                        .cloned()
+                                        .filter(|&s| s != epsilon_idx)
+                                        .collect();
+                                    stack.push(if new_production.is_empty() {
+                                        vec![epsilon_idx]
                                     } else {
-                                        nt.productions.push(new_production.collect())
-                                    }
+                                        new_production
+                                    });
                                 }
                             }
                             std::cmp::Ordering::Equal => {
@@ -60,15 +235,31 @@ impl Grammar {
                 }
             }
 
-            if recursive_productions.len() > 0 {
+            if !recursive_productions.is_empty() {
                 let nt_prime_idx = offset + new_non_terminals.len();
-                for production in &mut nt.productions {
-                    production.push(nt_prime_idx);
-                }
-                for production in &mut recursive_productions {
-                    production.push(nt_prime_idx);
+                let with_prime = |productions: &Vec<Vec<usize>>| {
+                    productions
+                        .iter()
+                        .map(|production| {
+                            production
+                                .iter()
+                                .cloned()
+                                .filter(|&s| s != epsilon_idx)
+                                .chain([nt_prime_idx])
+                                .collect()
+                        })
+                        .collect::<Vec<Vec<usize>>>()
+                };
+                if epsilon_free {
+                    let productions = with_prime(&nt.productions);
+                    nt.productions.extend(productions);
+                    let productions = with_prime(&recursive_productions);
+                    recursive_productions.extend(productions);
+                } else {
+                    nt.productions = with_prime(&nt.productions);
+                    recursive_productions = with_prime(&recursive_productions);
+                    recursive_productions.push(vec![epsilon_idx]);
                 }
-                recursive_productions.push(vec![epsilon_idx]);
                 new_non_terminals.push(NonTerminal {
                     index: nt_prime_idx,
                     nullable: false,
@@ -88,5 +279,7 @@ impl Grammar {
 
         self.invalidate_nullable_first_follow();
         self.calculate_nullable_first_follow();
+
+        Ok(())
     }
 }
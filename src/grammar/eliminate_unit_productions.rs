@@ -0,0 +1,137 @@
+use std::collections::{HashMap, VecDeque};
+
+use crowbook_text_processing::escape;
+use serde::Serialize;
+
+use super::{Grammar, EPSILON};
+
+#[derive(Debug, Serialize)]
+pub struct UnitProductionReport {
+    /// (A, B): the non-unit productions of B were copied into A
+    pub inlined: Vec<(String, String)>,
+    pub cycles: Vec<Vec<String>>,
+}
+
+impl UnitProductionReport {
+    pub fn to_plaintext(&self) -> String {
+        let inlined = self.inlined.iter().map(|(a, b)| format!("{} =>* {}", a, b));
+        let cycles = self
+            .cycles
+            .iter()
+            .map(|cycle| format!("cycle: {}", cycle.join(", ")));
+        inlined.chain(cycles).collect::<Vec<_>>().join("\n")
+    }
+
+    pub fn to_latex(&self) -> String {
+        let inlined = self.inlined.iter().map(|(a, b)| {
+            format!(
+                "{} $\\Rightarrow^*$ {}\\\\",
+                escape::tex(a.as_str()),
+                escape::tex(b.as_str())
+            )
+        });
+        let cycles = self.cycles.iter().map(|cycle| {
+            format!(
+                "cycle: {}\\\\",
+                cycle
+                    .iter()
+                    .map(|s| escape::tex(s.as_str()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        });
+        "\\begin{tabular}{l}\n".to_string()
+            + &inlined.chain(cycles).collect::<Vec<_>>().join("\n")
+            + "\n\\end{tabular}"
+    }
+}
+
+impl Grammar {
+    pub fn eliminate_unit_productions(&mut self) -> UnitProductionReport {
+        let epsilon_idx = self.get_symbol_index(EPSILON).unwrap();
+        let is_unit = |g: &Grammar, production: &[usize]| {
+            production.len() == 1
+                && production[0] != epsilon_idx
+                && g.symbols[production[0]].non_terminal().is_some()
+        };
+
+        let non_terminals: Vec<usize> = self.non_terminal_iter().map(|nt| nt.index).collect();
+
+        // closure[A]: every B with A =>* B using unit productions only, in BFS order
+        let mut closure: HashMap<usize, Vec<usize>> = HashMap::new();
+        for &a in &non_terminals {
+            let mut reachable = vec![a];
+            let mut q: VecDeque<usize> = VecDeque::new();
+            q.push_back(a);
+            while let Some(u) = q.pop_front() {
+                for production in &self.symbols[u].non_terminal().unwrap().productions {
+                    if is_unit(self, production) && !reachable.contains(&production[0]) {
+                        reachable.push(production[0]);
+                        q.push_back(production[0]);
+                    }
+                }
+            }
+            closure.insert(a, reachable);
+        }
+
+        let mut report = UnitProductionReport {
+            inlined: Vec::new(),
+            cycles: Vec::new(),
+        };
+
+        let mut in_cycle: Vec<usize> = Vec::new();
+        for &a in &non_terminals {
+            if in_cycle.contains(&a) {
+                continue;
+            }
+            let self_loop = self.symbols[a]
+                .non_terminal()
+                .unwrap()
+                .productions
+                .iter()
+                .any(|production| production == &[a]);
+            let cycle: Vec<usize> = closure[&a]
+                .iter()
+                .cloned()
+                .filter(|b| closure[b].contains(&a))
+                .collect();
+            if cycle.len() > 1 || self_loop {
+                in_cycle.extend(cycle.iter().cloned());
+                report.cycles.push(
+                    cycle
+                        .iter()
+                        .map(|&s| self.get_symbol_name(s).to_string())
+                        .collect(),
+                );
+            }
+        }
+
+        let mut new_productions: Vec<Vec<Vec<usize>>> = Vec::new();
+        for &a in &non_terminals {
+            let mut productions: Vec<Vec<usize>> = Vec::new();
+            for &b in &closure[&a] {
+                if b != a {
+                    report.inlined.push((
+                        self.get_symbol_name(a).to_string(),
+                        self.get_symbol_name(b).to_string(),
+                    ));
+                }
+                for production in &self.symbols[b].non_terminal().unwrap().productions {
+                    if !is_unit(self, production) && !productions.contains(production) {
+                        productions.push(production.clone());
+                    }
+                }
+            }
+            new_productions.push(productions);
+        }
+
+        for (nt, productions) in self.non_terminal_iter_mut().zip(new_productions) {
+            nt.productions = productions;
+        }
+
+        self.invalidate_nullable_first_follow();
+        self.calculate_nullable_first_follow();
+
+        report
+    }
+}
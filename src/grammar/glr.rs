@@ -0,0 +1,464 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+};
+
+use crowbook_text_processing::escape;
+use serde::Serialize;
+
+use super::{
+    lr_fsm::{LRParsingTable, LRParsingTableAction},
+    parse_tree::ParseTree,
+    END_MARK, EPSILON,
+};
+
+// A symbol node of the shared packed parse forest, each family is one way
+// (a packed node) to derive tokens[start..end] from the symbol
+#[derive(Debug, Clone, Serialize)]
+pub struct SPPFNode {
+    pub symbol: String,
+    pub start: usize,
+    pub end: usize,
+    pub families: Vec<Vec<usize>>,
+    pub terminal: bool,
+}
+
+#[derive(Serialize)]
+pub struct GLRParse {
+    pub tokens: Vec<String>,
+    pub accepted: bool,
+    pub forest: Vec<SPPFNode>,
+    pub root: Option<usize>,
+    // at most the requested number of trees
+    pub trees: Vec<ParseTree>,
+}
+
+impl SPPFNode {
+    fn label(&self) -> String {
+        format!("{}({},{})", self.symbol, self.start, self.end)
+    }
+}
+
+impl GLRParse {
+    // the non-terminal nodes reachable from the root, or all of them when rejected
+    fn listed_nodes(&self) -> Vec<usize> {
+        let mut nodes: Vec<usize> = match self.root {
+            Some(root) => {
+                let mut visited: HashSet<usize> = HashSet::new();
+                let mut stack = vec![root];
+                while let Some(u) = stack.pop() {
+                    if visited.insert(u) {
+                        stack.extend(self.forest[u].families.iter().flatten());
+                    }
+                }
+                visited.into_iter().collect()
+            }
+            None => (0..self.forest.len()).collect(),
+        };
+        nodes.retain(|&u| !self.forest[u].terminal);
+        nodes.sort_by_key(|&u| {
+            (
+                self.forest[u].start,
+                std::cmp::Reverse(self.forest[u].end),
+                u,
+            )
+        });
+        nodes
+    }
+
+    fn family_to_plaintext(&self, family: &[usize]) -> String {
+        if family.is_empty() {
+            EPSILON.to_string()
+        } else {
+            family
+                .iter()
+                .map(|&v| self.forest[v].label())
+                .collect::<Vec<_>>()
+                .join(" ")
+        }
+    }
+
+    pub fn to_plaintext(&self) -> String {
+        let mut output: Vec<String> = self
+            .listed_nodes()
+            .iter()
+            .map(|&u| {
+                let n = &self.forest[u];
+                format!(
+                    "{} -> {}",
+                    n.label(),
+                    n.families
+                        .iter()
+                        .map(|family| self.family_to_plaintext(family))
+                        .collect::<Vec<_>>()
+                        .join(" | ")
+                )
+            })
+            .collect();
+
+        output.push(if self.accepted {
+            format!("accepted, {} tree(s) listed", self.trees.len())
+        } else {
+            "rejected".to_string()
+        });
+        for (i, tree) in self.trees.iter().enumerate() {
+            output.push(format!("tree {}:", i + 1));
+            output.push(tree.to_plaintext());
+        }
+        output.join("\n")
+    }
+
+    pub fn to_latex(&self) -> String {
+        let rows: Vec<String> = self
+            .listed_nodes()
+            .iter()
+            .map(|&u| {
+                let n = &self.forest[u];
+                format!(
+                    "{} & {}\\\\",
+                    escape::tex(n.label().as_str()),
+                    n.families
+                        .iter()
+                        .map(
+                            |family| escape::tex(self.family_to_plaintext(family).as_str())
+                                .replace(EPSILON, "$\\epsilon$")
+                        )
+                        .collect::<Vec<_>>()
+                        .join(" $\\mid$ ")
+                )
+            })
+            .collect();
+
+        let mut output = "\\begin{tabular}{l|l}\n".to_string()
+            + "node & packed nodes\\\\\\hline\n"
+            + &rows.join("\n")
+            + "\n\\end{tabular}";
+        for tree in &self.trees {
+            output += "\n\n";
+            output += &tree.to_latex();
+        }
+        output
+    }
+
+    pub fn to_dot(&self) -> String {
+        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut lines: Vec<String> = Vec::new();
+        let mut listed: Vec<usize> = self.listed_nodes();
+        for &u in &listed.clone() {
+            for family in &self.forest[u].families {
+                for &v in family {
+                    if self.forest[v].terminal && !listed.contains(&v) {
+                        listed.push(v);
+                    }
+                }
+            }
+        }
+
+        for &u in &listed {
+            let n = &self.forest[u];
+            let shape = if n.terminal { "box" } else { "ellipse" };
+            lines.push(format!(
+                "  n{} [label=\"{}\", shape={}];",
+                u,
+                escape(n.label().as_str()),
+                shape
+            ));
+            for (i, family) in n.families.iter().enumerate() {
+                // packed nodes
+                lines.push(format!("  p{}_{} [label=\"\", shape=point];", u, i));
+                lines.push(format!("  n{} -> p{}_{};", u, u, i));
+                if family.is_empty() {
+                    lines.push(format!(
+                        "  e{}_{} [label=\"{}\", shape=plaintext];",
+                        u, i, EPSILON
+                    ));
+                    lines.push(format!("  p{}_{} -> e{}_{};", u, i, u, i));
+                }
+                for &v in family {
+                    lines.push(format!("  p{}_{} -> n{};", u, i, v));
+                }
+            }
+        }
+        "digraph {\n  ordering=out;\n".to_string() + &lines.join("\n") + "\n}"
+    }
+}
+
+// graph-structured stack
+struct GSSNode {
+    state: usize,
+    level: usize,
+    // (predecessor, forest node of the symbol between them)
+    edges: Vec<(usize, usize)>,
+}
+
+struct GLRParser<'a> {
+    table: &'a LRParsingTable,
+    gss: Vec<GSSNode>,
+    gss_map: HashMap<(usize, usize), usize>,
+    forest: Vec<SPPFNode>,
+    forest_map: HashMap<(String, usize, usize), usize>,
+}
+
+impl GLRParser<'_> {
+    fn gss_node(&mut self, state: usize, level: usize) -> (usize, bool) {
+        if let Some(&id) = self.gss_map.get(&(state, level)) {
+            return (id, false);
+        }
+        let id = self.gss.len();
+        self.gss.push(GSSNode {
+            state,
+            level,
+            edges: Vec::new(),
+        });
+        self.gss_map.insert((state, level), id);
+        (id, true)
+    }
+
+    fn forest_node(&mut self, symbol: &str, start: usize, end: usize, terminal: bool) -> usize {
+        let key = (symbol.to_string(), start, end);
+        if let Some(&id) = self.forest_map.get(&key) {
+            return id;
+        }
+        let id = self.forest.len();
+        self.forest.push(SPPFNode {
+            symbol: symbol.to_string(),
+            start,
+            end,
+            families: Vec::new(),
+            terminal,
+        });
+        self.forest_map.insert(key, id);
+        id
+    }
+
+    fn add_edge(&mut self, from: usize, to: usize, label: usize) -> bool {
+        if self.gss[from].edges.contains(&(to, label)) {
+            return false;
+        }
+        self.gss[from].edges.push((to, label));
+        true
+    }
+
+    // every (node, labels) reachable by popping n symbols from u
+    fn paths(&self, u: usize, n: usize) -> Vec<(usize, Vec<usize>)> {
+        if n == 0 {
+            return vec![(u, Vec::new())];
+        }
+        let mut ret: Vec<(usize, Vec<usize>)> = Vec::new();
+        for &(v, label) in &self.gss[u].edges {
+            for (w, mut labels) in self.paths(v, n - 1) {
+                labels.push(label);
+                ret.push((w, labels));
+            }
+        }
+        ret
+    }
+
+    // applies reductions at this level until nothing changes
+    fn reduce(&mut self, level: usize, col: usize) {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            let mut i = 0;
+            while i < self.gss.len() {
+                if self.gss[i].level != level {
+                    i += 1;
+                    continue;
+                }
+                let table = self.table;
+                for action in &table.action[self.gss[i].state][col] {
+                    let (left, right) = match action {
+                        LRParsingTableAction::Reduce((left, right)) => (left, right),
+                        _ => continue,
+                    };
+                    let n = right.iter().filter(|s| *s != EPSILON).count();
+                    let nt = table.non_terminals.iter().position(|s| s == left).unwrap();
+                    for (v, labels) in self.paths(i, n) {
+                        let next = match table.goto[self.gss[v].state][nt] {
+                            Some(next) => next,
+                            None => continue,
+                        };
+                        let node = self.forest_node(left, self.gss[v].level, level, false);
+                        if !self.forest[node].families.contains(&labels) {
+                            self.forest[node].families.push(labels);
+                            changed = true;
+                        }
+                        let (w, new) = self.gss_node(next, level);
+                        changed |= new;
+                        changed |= self.add_edge(w, v, node);
+                    }
+                }
+                i += 1;
+            }
+        }
+    }
+}
+
+// (node, family, offset): the children of a family of the node from offset on
+type FamilySuffix = (usize, usize, usize);
+
+struct TreeEnumerator<'a> {
+    forest: &'a [SPPFNode],
+    limit: usize,
+    // results are only memoized when no cycle was cut while enumerating them
+    cuts: Cell<usize>,
+    tree_memo: RefCell<HashMap<usize, Vec<ParseTree>>>,
+    sequence_memo: RefCell<HashMap<FamilySuffix, Vec<Vec<ParseTree>>>>,
+}
+
+impl TreeEnumerator<'_> {
+    fn trees(&self, node: usize, path: &mut HashSet<usize>) -> Vec<ParseTree> {
+        let n = &self.forest[node];
+        if n.terminal {
+            return vec![ParseTree {
+                symbol: n.symbol.clone(),
+                children: Vec::new(),
+            }];
+        }
+        if let Some(trees) = self.tree_memo.borrow().get(&node) {
+            return trees.clone();
+        }
+        // a cycle (A =>+ A) would give infinitely many trees
+        if !path.insert(node) {
+            self.cuts.set(self.cuts.get() + 1);
+            return Vec::new();
+        }
+        let cuts = self.cuts.get();
+
+        let mut ret: Vec<ParseTree> = Vec::new();
+        'outer: for f in 0..n.families.len() {
+            for children in self.sequences(node, f, 0, path) {
+                ret.push(ParseTree {
+                    symbol: n.symbol.clone(),
+                    children: if children.is_empty() {
+                        vec![ParseTree {
+                            symbol: EPSILON.to_string(),
+                            children: Vec::new(),
+                        }]
+                    } else {
+                        children
+                    },
+                });
+                if ret.len() >= self.limit {
+                    break 'outer;
+                }
+            }
+        }
+
+        path.remove(&node);
+        if self.cuts.get() == cuts {
+            self.tree_memo.borrow_mut().insert(node, ret.clone());
+        }
+        ret
+    }
+
+    // the ways for the children of family f of the node from offset on
+    fn sequences(
+        &self,
+        node: usize,
+        f: usize,
+        offset: usize,
+        path: &mut HashSet<usize>,
+    ) -> Vec<Vec<ParseTree>> {
+        let family = &self.forest[node].families[f];
+        let first = match family.get(offset) {
+            Some(&first) => first,
+            None => return vec![Vec::new()],
+        };
+        let key = (node, f, offset);
+        if let Some(sequences) = self.sequence_memo.borrow().get(&key) {
+            return sequences.clone();
+        }
+        let cuts = self.cuts.get();
+
+        let mut ret: Vec<Vec<ParseTree>> = Vec::new();
+        // the rest is enumerated first, a dead rest makes the head irrelevant
+        let tails = self.sequences(node, f, offset + 1, path);
+        if !tails.is_empty() {
+            let heads = self.trees(first, path);
+            'outer: for head in &heads {
+                for tail in &tails {
+                    ret.push(std::iter::once(head.clone()).chain(tail.clone()).collect());
+                    if ret.len() >= self.limit {
+                        break 'outer;
+                    }
+                }
+            }
+        }
+
+        if self.cuts.get() == cuts {
+            self.sequence_memo.borrow_mut().insert(key, ret.clone());
+        }
+        ret
+    }
+}
+
+impl LRParsingTable {
+    pub fn glr_parse(&self, tokens: &[&str], limit: usize) -> GLRParse {
+        let mut p = GLRParser {
+            table: self,
+            gss: Vec::new(),
+            gss_map: HashMap::new(),
+            forest: Vec::new(),
+            forest_map: HashMap::new(),
+        };
+        p.gss_node(0, 0);
+
+        let input: Vec<&str> = tokens.iter().cloned().chain([END_MARK]).collect();
+        let mut root: Option<usize> = None;
+        for (level, &a) in input.iter().enumerate() {
+            let col = match self.terminals.iter().position(|t| t == a) {
+                Some(col) => col,
+                None => break,
+            };
+            p.reduce(level, col);
+
+            let frontier: Vec<usize> = (0..p.gss.len())
+                .filter(|&u| p.gss[u].level == level)
+                .collect();
+            for &u in &frontier {
+                for action in &self.action[p.gss[u].state][col] {
+                    match action {
+                        LRParsingTableAction::Shift(next) => {
+                            let label = p.forest_node(a, level, level + 1, true);
+                            let (w, _) = p.gss_node(*next, level + 1);
+                            p.add_edge(w, u, label);
+                        }
+                        LRParsingTableAction::Accept => {
+                            // the accepting state is goto(0, S), its edge carries S
+                            root = p.gss[u]
+                                .edges
+                                .iter()
+                                .find(|&&(v, _)| p.gss[v].level == 0 && p.gss[v].state == 0)
+                                .map(|&(_, label)| label);
+                        }
+                        LRParsingTableAction::Reduce(_) => {}
+                    }
+                }
+            }
+            if !p.gss.iter().any(|u| u.level == level + 1) {
+                break;
+            }
+        }
+
+        let trees = match root {
+            Some(root) if limit > 0 => TreeEnumerator {
+                forest: &p.forest,
+                limit,
+                cuts: Cell::new(0),
+                tree_memo: RefCell::new(HashMap::new()),
+                sequence_memo: RefCell::new(HashMap::new()),
+            }
+            .trees(root, &mut HashSet::new()),
+            _ => Vec::new(),
+        };
+
+        GLRParse {
+            tokens: tokens.iter().map(|s| s.to_string()).collect(),
+            accepted: root.is_some(),
+            forest: p.forest,
+            root,
+            trees,
+        }
+    }
+}
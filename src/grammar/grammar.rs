@@ -53,6 +53,12 @@ pub struct Grammar {
     pub start_symbol: Option<usize>,
 }
 
+impl Default for Grammar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Grammar {
     pub fn new() -> Self {
         let mut g = Self {
@@ -137,6 +143,50 @@ impl Grammar {
         name
     }
 
+    pub fn remove_symbols(&mut self, removed: &HashSet<usize>) {
+        // epsilon and end mark are always kept
+        let removed: HashSet<usize> = removed
+            .iter()
+            .cloned()
+            .filter(|&idx| idx != 0 && self.get_symbol_name(idx) != super::END_MARK)
+            .collect();
+        if removed.is_empty() {
+            return;
+        }
+
+        let mut map: HashMap<usize, usize> = HashMap::new();
+        for idx in 0..self.symbols.len() {
+            if !removed.contains(&idx) {
+                map.insert(idx, map.len());
+            }
+        }
+
+        let symbols = std::mem::take(&mut self.symbols);
+        for (idx, mut symbol) in symbols.into_iter().enumerate() {
+            if removed.contains(&idx) {
+                continue;
+            }
+            if let Symbol::NonTerminal(nt) = &mut symbol {
+                nt.index = map[&idx];
+                nt.productions = std::mem::take(&mut nt.productions)
+                    .into_iter()
+                    .filter(|production| production.iter().all(|s| map.contains_key(s)))
+                    .map(|production| production.iter().map(|s| map[s]).collect())
+                    .collect();
+            }
+            self.symbols.push(symbol);
+        }
+
+        self.symbol_table = self
+            .symbol_table
+            .iter()
+            .filter_map(|(name, idx)| map.get(idx).map(|&idx| (name.clone(), idx)))
+            .collect();
+        self.start_symbol = self.start_symbol.and_then(|idx| map.get(&idx).cloned());
+
+        self.invalidate_nullable_first_follow();
+    }
+
     pub fn invalidate_nullable_first_follow(&mut self) {
         self.valid_nullable_first_follow = false;
         self.reset_nullable_first_follow();
@@ -150,7 +200,7 @@ impl Grammar {
         self.valid_nullable_first_follow = true;
     }
 
-    pub fn production_to_vec_str(&self, production: &Vec<usize>) -> Vec<&str> {
+    pub fn production_to_vec_str(&self, production: &[usize]) -> Vec<&str> {
         production
             .iter()
             .map(|idx| self.get_symbol_name(*idx))
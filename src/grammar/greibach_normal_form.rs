@@ -0,0 +1,66 @@
+use std::collections::HashSet;
+
+use super::{eliminate_left_recursion::LeftRecursionError, Grammar, EPSILON};
+
+impl Grammar {
+    fn substitute_leading_non_terminals(&mut self, idx: usize, done: &mut HashSet<usize>) {
+        if !done.insert(idx) {
+            return;
+        }
+
+        let epsilon_idx = self.get_symbol_index(EPSILON).unwrap();
+        let productions = self.symbols[idx]
+            .non_terminal()
+            .unwrap()
+            .productions
+            .clone();
+        let mut new_productions: Vec<Vec<usize>> = Vec::new();
+        for production in productions {
+            let first = production[0];
+            if first == epsilon_idx || self.symbols[first].non_terminal().is_none() {
+                if !new_productions.contains(&production) {
+                    new_productions.push(production);
+                }
+                continue;
+            }
+
+            // no left recursion is left, so this recursion terminates
+            self.substitute_leading_non_terminals(first, done);
+            for prefix in &self.symbols[first].non_terminal().unwrap().productions {
+                let new_production: Vec<usize> = prefix
+                    .iter()
+                    .chain(production.iter().skip(1))
+                    .cloned()
+                    .collect();
+                if !new_productions.contains(&new_production) {
+                    new_productions.push(new_production);
+                }
+            }
+        }
+        self.symbols[idx].mut_non_terminal().unwrap().productions = new_productions;
+    }
+
+    pub fn to_greibach_normal_form(&mut self) -> Result<(), LeftRecursionError> {
+        if self.start_symbol.is_none() {
+            return Ok(());
+        }
+
+        // CNF has no ϵ-productions (except for the start symbol) and no unit productions,
+        // and puts only non-terminals after the first symbol
+        self.to_chomsky_normal_form();
+        self.substitute_and_eliminate_left_recursion(true)?;
+
+        let non_terminals: Vec<usize> = self.non_terminal_iter().map(|nt| nt.index).collect();
+        let mut done: HashSet<usize> = HashSet::new();
+        for idx in non_terminals {
+            self.substitute_leading_non_terminals(idx, &mut done);
+        }
+
+        self.remove_useless_symbols();
+
+        self.invalidate_nullable_first_follow();
+        self.calculate_nullable_first_follow();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use super::{Grammar, EPSILON};
+
+fn find_common_prefix(
+    productions: &[Vec<usize>],
+    epsilon_idx: usize,
+) -> Option<(Vec<usize>, Vec<usize>)> {
+    for (i, production) in productions.iter().enumerate() {
+        let head = match production.first() {
+            Some(&head) if head != epsilon_idx => head,
+            _ => continue,
+        };
+
+        let group: Vec<usize> = (i..productions.len())
+            .filter(|&j| productions[j].first() == Some(&head))
+            .collect();
+        if group.len() < 2 {
+            continue;
+        }
+
+        let mut prefix = production.clone();
+        for &j in &group[1..] {
+            let len = prefix
+                .iter()
+                .zip(productions[j].iter())
+                .take_while(|(a, b)| a == b)
+                .count();
+            prefix.truncate(len);
+        }
+        return Some((prefix, group));
+    }
+    None
+}
+
+impl Grammar {
+    pub fn left_factor(&mut self) {
+        let epsilon_idx = self.get_symbol_index(EPSILON).unwrap();
+
+        // skip epsilon, newly added non-terminals are appended and visited as well
+        let mut i = 1;
+        while i < self.symbols.len() {
+            while let Some((prefix, group)) = self.symbols[i]
+                .non_terminal()
+                .and_then(|nt| find_common_prefix(&nt.productions, epsilon_idx))
+            {
+                let name = self.get_symbol_prime_name(self.get_symbol_name(i).to_string());
+                let nt_prime_idx = self.add_non_terminal(&name);
+
+                let old_productions =
+                    std::mem::take(&mut self.symbols[i].mut_non_terminal().unwrap().productions);
+                let mut factored_productions: Vec<Vec<usize>> = Vec::new();
+                let mut productions: Vec<Vec<usize>> = Vec::new();
+                for (j, production) in old_productions.into_iter().enumerate() {
+                    if !group.contains(&j) {
+                        productions.push(production);
+                        continue;
+                    }
+
+                    let rest = if production.len() > prefix.len() {
+                        production[prefix.len()..].to_vec()
+                    } else {
+                        vec![epsilon_idx]
+                    };
+                    if !factored_productions.contains(&rest) {
+                        factored_productions.push(rest);
+                    }
+
+                    if j == group[0] {
+                        productions.push(prefix.iter().cloned().chain([nt_prime_idx]).collect());
+                    }
+                }
+
+                self.symbols[i].mut_non_terminal().unwrap().productions = productions;
+                self.symbols[nt_prime_idx]
+                    .mut_non_terminal()
+                    .unwrap()
+                    .productions = factored_productions;
+            }
+            i += 1;
+        }
+
+        self.invalidate_nullable_first_follow();
+        self.calculate_nullable_first_follow();
+    }
+}
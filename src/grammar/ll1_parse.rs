@@ -0,0 +1,245 @@
+use std::collections::HashSet;
+
+use crowbook_text_processing::escape;
+use serde::Serialize;
+
+use super::{
+    ll1_parsing_table::LL1ParsingTable,
+    pretty_print::{production_right_to_latex, table_to_plaintext},
+    END_MARK, EPSILON,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum LL1ParseAction {
+    Predict(String, Vec<String>),
+    Match(String),
+    Accept,
+    Error(String),
+    Recover(LL1Recovery),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum LL1Recovery {
+    // the input symbol is discarded
+    Skip(String),
+    // the non-terminal on the stack is discarded
+    Pop(String),
+    // the terminal on the stack is taken as missing from the input
+    Insert(String),
+}
+
+impl LL1Recovery {
+    pub fn to_plaintext(&self) -> String {
+        match self {
+            LL1Recovery::Skip(a) => format!("skip {}", a),
+            LL1Recovery::Pop(left) => format!("pop {}", left),
+            LL1Recovery::Insert(t) => format!("insert {}", t),
+        }
+    }
+}
+
+impl LL1ParseAction {
+    pub fn to_plaintext(&self) -> String {
+        match self {
+            LL1ParseAction::Predict(left, right) => format!("{} -> {}", left, right.join(" ")),
+            LL1ParseAction::Match(t) => format!("match {}", t),
+            LL1ParseAction::Accept => "accept".to_string(),
+            LL1ParseAction::Error(e) => format!("error: {}", e),
+            LL1ParseAction::Recover(r) => format!("recover: {}", r.to_plaintext()),
+        }
+    }
+
+    pub fn to_latex(&self, terminal_set: &HashSet<&str>) -> String {
+        match self {
+            LL1ParseAction::Predict(left, right) => format!(
+                "{} \\rightarrow {}",
+                escape::tex(left.as_str()),
+                production_right_to_latex(right.iter().map(|s| s.as_str()), terminal_set)
+            ),
+            LL1ParseAction::Match(t) => format!("\\text{{match {}}}", escape::tex(t.as_str())),
+            LL1ParseAction::Accept => "\\text{accept}".to_string(),
+            LL1ParseAction::Error(e) => format!("\\text{{error: {}}}", escape::tex(e.as_str())),
+            LL1ParseAction::Recover(r) => format!(
+                "{{\\color{{red}}\\text{{recover: {}}}}}",
+                escape::tex(r.to_plaintext().as_str())
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LL1ParseStep {
+    // bottom to top
+    pub stack: Vec<String>,
+    pub input: Vec<String>,
+    pub action: LL1ParseAction,
+}
+
+#[derive(Serialize)]
+pub struct LL1ParseTrace {
+    pub steps: Vec<LL1ParseStep>,
+    // false when the input had to be repaired
+    pub accepted: bool,
+    pub errors: usize,
+    #[serde(skip)]
+    pub(super) terminals: Vec<String>,
+}
+
+impl LL1ParseTrace {
+    pub fn to_plaintext(&self) -> String {
+        let mut rows: Vec<Vec<String>> = vec![vec![
+            "Stack".to_string(),
+            "Input".to_string(),
+            "Action".to_string(),
+        ]];
+        for step in &self.steps {
+            rows.push(vec![
+                step.stack.join(" "),
+                step.input.join(" "),
+                step.action.to_plaintext(),
+            ]);
+        }
+        table_to_plaintext(&rows, &[false, true, false])
+    }
+
+    pub fn to_latex(&self) -> String {
+        let terminal_set: HashSet<&str> = self.terminals.iter().map(|s| s.as_str()).collect();
+        let content = self
+            .steps
+            .iter()
+            .map(|step| {
+                format!(
+                    "{} & {} & {}",
+                    production_right_to_latex(step.stack.iter().map(|s| s.as_str()), &terminal_set),
+                    production_right_to_latex(step.input.iter().map(|s| s.as_str()), &terminal_set),
+                    step.action.to_latex(&terminal_set)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\\\\\n");
+
+        "\\[\\begin{array}{l|r|l}\n".to_string()
+            + "\\text{Stack} & \\text{Input} & \\text{Action}\\\\\\hline\n"
+            + &content
+            + "\n\\end{array}\\]"
+    }
+}
+
+impl LL1ParsingTable<'_> {
+    pub fn parse(&self, tokens: &[&str]) -> LL1ParseTrace {
+        self.parse_with_recovery(tokens, 0)
+    }
+
+    // Recovers from at most `max_errors` syntax errors:
+    // a mismatched terminal on the stack is taken as missing (phrase level),
+    // an empty M[A, a] pops A if a is in FOLLOW(A) and skips a otherwise (panic mode).
+    pub fn parse_with_recovery(&self, tokens: &[&str], max_errors: usize) -> LL1ParseTrace {
+        let mut trace = LL1ParseTrace {
+            steps: Vec::new(),
+            accepted: false,
+            errors: 0,
+            terminals: self.terminals.iter().map(|s| s.to_string()).collect(),
+        };
+
+        let mut stack: Vec<&str> = vec![END_MARK];
+        stack.extend(self.start);
+        let input: Vec<&str> = tokens.iter().cloned().chain([END_MARK]).collect();
+        let mut pos: usize = 0;
+
+        loop {
+            let top = *stack.last().unwrap();
+            let a = input[pos];
+            let mut step = LL1ParseStep {
+                stack: stack.iter().map(|s| s.to_string()).collect(),
+                input: input[pos..].iter().map(|s| s.to_string()).collect(),
+                action: LL1ParseAction::Accept,
+            };
+
+            let row = self.rows.iter().find(|(left, _)| *left == top);
+            let col = self.terminals.iter().position(|t| *t == a);
+
+            // (message, recovery)
+            let error: Option<(String, LL1ParseAction)> = if let Some((left, row)) = row {
+                match col.map(|col| &row[col].rights) {
+                    None => Some((
+                        format!("unknown terminal {}", a),
+                        LL1ParseAction::Recover(LL1Recovery::Skip(a.to_string())),
+                    )),
+                    Some(rights) if rights.is_empty() => {
+                        let recovery = if a == END_MARK
+                            || self.follow.get(left).is_some_and(|f| f.contains(a))
+                        {
+                            LL1Recovery::Pop(left.to_string())
+                        } else {
+                            LL1Recovery::Skip(a.to_string())
+                        };
+                        Some((
+                            format!("no production for M[{}, {}]", left, a),
+                            LL1ParseAction::Recover(recovery),
+                        ))
+                    }
+                    Some(rights) if rights.len() > 1 => {
+                        step.action =
+                            LL1ParseAction::Error(format!("conflict at M[{}, {}]", left, a));
+                        None
+                    }
+                    Some(rights) => {
+                        stack.pop();
+                        stack.extend(rights[0].iter().rev().filter(|&&s| s != EPSILON));
+                        step.action = LL1ParseAction::Predict(
+                            left.to_string(),
+                            rights[0].iter().map(|s| s.to_string()).collect(),
+                        );
+                        None
+                    }
+                }
+            } else if top == a {
+                if top == END_MARK {
+                    trace.accepted = trace.errors == 0;
+                    step.action = LL1ParseAction::Accept;
+                } else {
+                    stack.pop();
+                    pos += 1;
+                    step.action = LL1ParseAction::Match(a.to_string());
+                }
+                None
+            } else if top == END_MARK {
+                Some((
+                    format!("expected {}, found {}", top, a),
+                    LL1ParseAction::Recover(LL1Recovery::Skip(a.to_string())),
+                ))
+            } else {
+                Some((
+                    format!("expected {}, found {}", top, a),
+                    LL1ParseAction::Recover(LL1Recovery::Insert(top.to_string())),
+                ))
+            };
+
+            if let Some((message, recovery)) = error {
+                if trace.errors < max_errors {
+                    trace.errors += 1;
+                    match &recovery {
+                        LL1ParseAction::Recover(LL1Recovery::Skip(_)) => pos += 1,
+                        _ => {
+                            stack.pop();
+                        }
+                    }
+                    step.action = recovery;
+                } else {
+                    step.action = LL1ParseAction::Error(message);
+                }
+            }
+
+            let stop = matches!(
+                step.action,
+                LL1ParseAction::Accept | LL1ParseAction::Error(_)
+            );
+            trace.steps.push(step);
+            if stop {
+                break;
+            }
+        }
+
+        trace
+    }
+}
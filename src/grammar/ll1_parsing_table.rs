@@ -1,15 +1,25 @@
-use crowbook_text_processing::escape::tex as escape_tex;
+use crowbook_text_processing::escape::{html as escape_html, tex as escape_tex};
 use serde::Serialize;
 use std::collections::{HashMap, HashSet};
 
 use crate::Grammar;
 
-use super::{pretty_print::ProductionOutput, EPSILON};
+use super::pretty_print::{
+    conflict_to_html, conflict_to_typst, table_to_html, table_to_markdown, table_to_typst,
+    typst_string, ProductionOutput,
+};
 
 #[derive(Serialize)]
 pub struct LL1ParsingTable<'a> {
-    terminals: Vec<&'a str>,
-    rows: Vec<(&'a str, Vec<ProductionOutput<'a>>)>,
+    pub(super) terminals: Vec<&'a str>,
+    pub(super) rows: Vec<(&'a str, Vec<ProductionOutput<'a>>)>,
+    #[serde(skip)]
+    pub(super) start: Option<&'a str>,
+    // synchronizing sets for panic-mode recovery
+    #[serde(skip)]
+    pub(super) follow: HashMap<&'a str, HashSet<&'a str>>,
+    #[serde(skip)]
+    pub(super) nullable: HashSet<&'a str>,
 }
 
 impl LL1ParsingTable<'_> {
@@ -43,6 +53,77 @@ impl LL1ParsingTable<'_> {
             .join("\n")
     }
 
+    pub fn to_markdown(&self) -> String {
+        let mut rows: Vec<Vec<String>> = vec![std::iter::once(String::new())
+            .chain(self.terminals.iter().map(|t| t.to_string()))
+            .collect()];
+        for (left, row) in &self.rows {
+            rows.push(
+                std::iter::once(left.to_string())
+                    .chain(row.iter().map(|productions| {
+                        let r = productions.to_plaintext(0, false);
+                        // conflicts in bold
+                        if productions.rights.len() > 1 {
+                            format!("**{}**", r)
+                        } else {
+                            r
+                        }
+                    }))
+                    .collect(),
+            );
+        }
+        table_to_markdown(&rows)
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut rows: Vec<Vec<String>> = vec![std::iter::once(String::new())
+            .chain(self.terminals.iter().map(|&t| escape_html(t).to_string()))
+            .collect()];
+        for (left, row) in &self.rows {
+            rows.push(
+                std::iter::once(escape_html(*left).to_string())
+                    .chain(row.iter().map(|productions| {
+                        let r = productions
+                            .rights
+                            .iter()
+                            .map(|right| {
+                                escape_html(format!("{} -> {}", left, right.join(" "))).to_string()
+                            })
+                            .collect::<Vec<_>>()
+                            .join("<br>");
+                        if productions.rights.len() > 1 {
+                            conflict_to_html(&r)
+                        } else {
+                            r
+                        }
+                    }))
+                    .collect(),
+            );
+        }
+        table_to_html(&rows)
+    }
+
+    pub fn to_typst(&self) -> String {
+        let mut rows: Vec<Vec<String>> = vec![std::iter::once(typst_string(""))
+            .chain(self.terminals.iter().map(|t| typst_string(t)))
+            .collect()];
+        for (left, row) in &self.rows {
+            rows.push(
+                std::iter::once(typst_string(left))
+                    .chain(row.iter().map(|productions| {
+                        let r = productions.to_plaintext(0, false);
+                        if productions.rights.len() > 1 {
+                            conflict_to_typst(&r)
+                        } else {
+                            typst_string(&r)
+                        }
+                    }))
+                    .collect(),
+            );
+        }
+        table_to_typst(&rows)
+    }
+
     pub fn to_latex(&self) -> String {
         let mut header: Vec<String> = vec![format!(
             "\\[\\begin{{array}}{{c{}}}\n",
@@ -73,7 +154,7 @@ impl LL1ParsingTable<'_> {
 }
 
 impl Grammar {
-    pub fn generate_ll1_parsing_table(&mut self) -> LL1ParsingTable {
+    pub fn generate_ll1_parsing_table(&mut self) -> LL1ParsingTable<'_> {
         if !self.is_nullable_first_follow_valid() {
             self.calculate_nullable_first_follow();
         }
@@ -96,27 +177,55 @@ impl Grammar {
                 terminals.len()
             ];
             for production in &nt.productions {
-                let first = self.calculate_first_for_production(production);
-
-                let production_string_iter =
-                    production.iter().map(|idx| self.get_symbol_name(*idx));
-
-                for col in first.iter().map(|idx| map[idx]) {
-                    row[col]
-                        .rights
-                        .push(production_string_iter.clone().collect::<Vec<_>>());
+                let mut cols: Vec<usize> = self
+                    .calculate_first_for_production(production)
+                    .iter()
+                    .map(|idx| map[idx])
+                    .collect();
+                // the production itself, not just ϵ, is predicted on FOLLOW
+                if production.iter().all(|&idx| {
+                    self.symbols[idx]
+                        .non_terminal()
+                        .is_some_and(|nt| nt.nullable)
+                }) {
+                    cols.extend(nt.follow.iter().map(|idx| map[idx]));
                 }
-            }
 
-            if nt.nullable {
-                for idx in &nt.follow {
-                    row[map[idx]].rights.push(vec![EPSILON]);
+                let right: Vec<&str> = production
+                    .iter()
+                    .map(|idx| self.get_symbol_name(*idx))
+                    .collect();
+                for col in cols {
+                    if !row[col].rights.contains(&right) {
+                        row[col].rights.push(right.clone());
+                    }
                 }
             }
 
             rows.push((left, row));
         }
 
-        LL1ParsingTable { terminals, rows }
+        LL1ParsingTable {
+            terminals,
+            rows,
+            start: self.start_symbol.map(|idx| self.get_symbol_name(idx)),
+            follow: self
+                .non_terminal_iter()
+                .map(|nt| {
+                    (
+                        nt.name.as_str(),
+                        nt.follow
+                            .iter()
+                            .map(|&idx| self.get_symbol_name(idx))
+                            .collect(),
+                    )
+                })
+                .collect(),
+            nullable: self
+                .non_terminal_iter()
+                .filter(|nt| nt.nullable)
+                .map(|nt| nt.name.as_str())
+                .collect(),
+        }
     }
 }
@@ -1,4 +1,4 @@
-use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 
 use serde::{Deserialize, Serialize};
 
@@ -56,69 +56,67 @@ impl LRItem {
         let mut extend: HashMap<usize, Option<HashSet<usize>>> = HashMap::new();
         let mut q: VecDeque<usize> = VecDeque::new();
 
-        let calculate_first = |production: &[String]| -> Vec<usize> {
-            g.calculate_first_for_production(
-                &production
-                    .iter()
-                    .map(|s| g.get_symbol_index(s).unwrap())
-                    .collect::<Vec<_>>(),
-            )
-            .into_iter()
-            .collect()
+        let is_nullable = |production: &[usize]| {
+            production
+                .iter()
+                .all(|&s| g.symbols[s].non_terminal().is_some_and(|nt| nt.nullable))
         };
 
         // use self.kernel to initialize self.extend
         for c in &self.kernel {
             if let Some(symbol) = c.production.get(c.position) {
                 if let Symbol::NonTerminal(nt) = g.get_symbol_by_name(symbol.as_str()) {
-                    if !extend.contains_key(&nt.index) {
-                        extend.insert(nt.index, if is_lr1 { Some(HashSet::new()) } else { None });
+                    if let Entry::Vacant(e) = extend.entry(nt.index) {
+                        e.insert(if is_lr1 { Some(HashSet::new()) } else { None });
                         q.push_back(nt.index);
                     }
 
                     if is_lr1 {
-                        let lookahead = if c.position + 1 < c.production.len() {
-                            calculate_first(&c.production[c.position + 1..])
-                        } else {
-                            c.lookahead
-                                .as_ref()
-                                .unwrap()
-                                .iter()
-                                .map(|s| g.get_symbol_index(s).unwrap())
-                                .collect()
-                        };
+                        let rest: Vec<usize> = c.production[c.position + 1..]
+                            .iter()
+                            .map(|s| g.get_symbol_index(s).unwrap())
+                            .collect();
+                        let mut lookahead = g.calculate_first_for_production(&rest);
+                        if is_nullable(&rest) {
+                            lookahead.extend(
+                                c.lookahead
+                                    .as_ref()
+                                    .unwrap()
+                                    .iter()
+                                    .map(|s| g.get_symbol_index(s).unwrap()),
+                            );
+                        }
                         extend
                             .get_mut(&nt.index)
                             .unwrap()
                             .as_mut()
                             .unwrap()
-                            .extend(lookahead.into_iter());
+                            .extend(lookahead);
                     }
                 }
             }
         }
 
-        // iteratively calculate self.extend
+        // iteratively calculate self.extend, a non-terminal is visited again when its lookahead grows
         while let Some(s_idx) = q.pop_front() {
             for production in &g.symbols[s_idx].non_terminal().unwrap().productions {
                 if let Symbol::NonTerminal(nt) = &g.symbols[production[0]] {
-                    if !extend.contains_key(&nt.index) {
-                        extend.insert(nt.index, if is_lr1 { Some(HashSet::new()) } else { None });
+                    if let Entry::Vacant(e) = extend.entry(nt.index) {
+                        e.insert(if is_lr1 { Some(HashSet::new()) } else { None });
                         q.push_back(nt.index);
                     }
 
                     if is_lr1 {
-                        let lookahead = if production.len() > 1 {
-                            g.calculate_first_for_production(&production[1..])
-                        } else {
-                            extend[&s_idx].as_ref().unwrap().clone()
-                        };
-                        extend
-                            .get_mut(&nt.index)
-                            .unwrap()
-                            .as_mut()
-                            .unwrap()
-                            .extend(lookahead);
+                        let mut lookahead = g.calculate_first_for_production(&production[1..]);
+                        if is_nullable(&production[1..]) {
+                            lookahead.extend(extend[&s_idx].as_ref().unwrap().iter().cloned());
+                        }
+                        let current = extend.get_mut(&nt.index).unwrap().as_mut().unwrap();
+                        let len = current.len();
+                        current.extend(lookahead);
+                        if current.len() != len && !q.contains(&nt.index) {
+                            q.push_back(nt.index);
+                        }
                     }
                 }
             }
@@ -127,13 +125,13 @@ impl LRItem {
         for (nt_idx, lookahead) in extend {
             let nt = g.symbols[nt_idx].non_terminal().unwrap();
 
-            let lookahead: Option<Vec<String>> = lookahead.and_then(|lookahead| {
+            let lookahead: Option<Vec<String>> = lookahead.map(|lookahead| {
                 let mut lookahead = lookahead
                     .iter()
                     .map(|&i| g.get_symbol_name(i).to_string())
                     .collect::<Vec<_>>();
                 lookahead.sort();
-                Some(lookahead)
+                lookahead
             });
 
             for production in &nt.productions {
@@ -177,10 +175,12 @@ impl LRItem {
 #[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum LRFSMType {
     LR0,
+    SLR1,
     LR1,
     LALR,
 }
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Serialize)]
 pub struct LRFSM {
     pub t: LRFSMType,
@@ -199,7 +199,7 @@ impl Grammar {
             return Err("start symbol is not set".to_string());
         }
 
-        if t == LRFSMType::LR0 && !self.is_nullable_first_follow_valid() {
+        if !self.is_nullable_first_follow_valid() {
             self.calculate_nullable_first_follow();
         }
 
@@ -235,7 +235,7 @@ impl Grammar {
 
                 if production.position < production.production.len() {
                     let e = production.production[production.position].clone();
-                    let item = edges.entry(e).or_insert(BTreeSet::new());
+                    let item = edges.entry(e).or_default();
                     item.insert(production.generate_next());
                 }
             }
@@ -325,7 +325,7 @@ impl Grammar {
             states,
             start: 0,
             end,
-            follow: if t == LRFSMType::LR0 {
+            follow: if t == LRFSMType::SLR1 {
                 let mut r: HashMap<String, Vec<String>> = HashMap::new();
                 r.insert(dummy_start, vec![END_MARK.to_string()]);
                 for nt in self.non_terminal_iter() {
@@ -396,8 +396,11 @@ impl LRFSM {
 
                     let lookahead = if let Some(lookahead) = &prodcution.lookahead {
                         lookahead
+                    } else if let Some(follow) = &self.follow {
+                        &follow[&prodcution.left]
                     } else {
-                        &self.follow.as_ref().unwrap()[&prodcution.left]
+                        // LR(0) reduces regardless of the next terminal
+                        &self.terminals
                     };
                     for terminal in lookahead {
                         action_row[terminal_idx_map[terminal.as_str()]].push(
@@ -0,0 +1,357 @@
+use std::collections::HashSet;
+
+use crowbook_text_processing::escape;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    lr_fsm::{LRParsingTable, LRParsingTableAction},
+    pretty_print::{production_right_to_latex, table_to_plaintext},
+    END_MARK, EPSILON,
+};
+
+// What to do when the ACTION cell holds more than one action
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum LRConflictPolicy {
+    // stop with an error
+    Refuse,
+    // shift over reduce, otherwise the first listed reduction (as yacc does)
+    PreferShift,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum LRParseAction {
+    Shift(usize),
+    Reduce(String, Vec<String>),
+    Accept,
+    Error(String),
+    Recover(LRRecovery),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum LRRecovery {
+    // the input symbol is discarded
+    Skip(String),
+    // the terminal is taken as missing from the input and shifted
+    Insert(String),
+    // states popped, input symbols discarded, error non-terminal pushed
+    Pop(usize, usize, String),
+}
+
+impl LRRecovery {
+    pub fn to_plaintext(&self) -> String {
+        match self {
+            LRRecovery::Skip(a) => format!("skip {}", a),
+            LRRecovery::Insert(t) => format!("insert {}", t),
+            LRRecovery::Pop(popped, skipped, nt) => format!(
+                "pop {} state(s), skip {} token(s), push {}",
+                popped, skipped, nt
+            ),
+        }
+    }
+}
+
+impl LRParseAction {
+    pub fn to_plaintext(&self) -> String {
+        match self {
+            LRParseAction::Shift(s) => format!("shift {}", s),
+            LRParseAction::Reduce(left, right) => {
+                format!("reduce {} -> {}", left, right.join(" "))
+            }
+            LRParseAction::Accept => "accept".to_string(),
+            LRParseAction::Error(e) => format!("error: {}", e),
+            LRParseAction::Recover(r) => format!("recover: {}", r.to_plaintext()),
+        }
+    }
+
+    pub fn to_latex(&self, terminal_set: &HashSet<&str>) -> String {
+        match self {
+            LRParseAction::Shift(s) => format!("\\text{{shift {}}}", s),
+            LRParseAction::Reduce(left, right) => format!(
+                "\\text{{reduce }} {} \\rightarrow {}",
+                escape::tex(left.as_str()),
+                production_right_to_latex(right.iter().map(|s| s.as_str()), terminal_set)
+            ),
+            LRParseAction::Accept => "\\text{accept}".to_string(),
+            LRParseAction::Error(e) => format!("\\text{{error: {}}}", escape::tex(e.as_str())),
+            LRParseAction::Recover(r) => format!(
+                "{{\\color{{red}}\\text{{recover: {}}}}}",
+                escape::tex(r.to_plaintext().as_str())
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LRParseStep {
+    // bottom to top
+    pub states: Vec<usize>,
+    pub symbols: Vec<String>,
+    pub input: Vec<String>,
+    pub action: LRParseAction,
+}
+
+#[derive(Serialize)]
+pub struct LRParseTrace {
+    pub steps: Vec<LRParseStep>,
+    // false when the input had to be repaired
+    pub accepted: bool,
+    pub errors: usize,
+    #[serde(skip)]
+    pub(super) terminals: Vec<String>,
+}
+
+impl LRParseTrace {
+    pub fn to_plaintext(&self) -> String {
+        let mut rows: Vec<Vec<String>> = vec![vec![
+            "State".to_string(),
+            "Symbol".to_string(),
+            "Input".to_string(),
+            "Action".to_string(),
+        ]];
+        for step in &self.steps {
+            rows.push(vec![
+                step.states
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                step.symbols.join(" "),
+                step.input.join(" "),
+                step.action.to_plaintext(),
+            ]);
+        }
+        table_to_plaintext(&rows, &[false, false, true, false])
+    }
+
+    pub fn to_latex(&self) -> String {
+        let terminal_set: HashSet<&str> = self.terminals.iter().map(|s| s.as_str()).collect();
+        let content = self
+            .steps
+            .iter()
+            .map(|step| {
+                format!(
+                    "{} & {} & {} & {}",
+                    step.states
+                        .iter()
+                        .map(|s| s.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" \\  "),
+                    production_right_to_latex(
+                        step.symbols.iter().map(|s| s.as_str()),
+                        &terminal_set
+                    ),
+                    production_right_to_latex(step.input.iter().map(|s| s.as_str()), &terminal_set),
+                    step.action.to_latex(&terminal_set)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\\\\\n");
+
+        "\\[\\begin{array}{l|l|r|l}\n".to_string()
+            + "\\text{State} & \\text{Symbol} & \\text{Input} & \\text{Action}\\\\\\hline\n"
+            + &content
+            + "\n\\end{array}\\]"
+    }
+}
+
+impl LRParsingTable {
+    pub fn parse(&self, tokens: &[&str], policy: LRConflictPolicy) -> LRParseTrace {
+        self.parse_with_recovery(tokens, policy, &[], 0)
+    }
+
+    // Recovers from at most `max_errors` syntax errors: the first terminal t whose only
+    // action is a shift into a state expecting the lookahead is inserted (phrase level),
+    // otherwise states are popped until one has a GOTO on an error non-terminal A, and
+    // input is discarded until it can follow A (panic mode).
+    pub fn parse_with_recovery(
+        &self,
+        tokens: &[&str],
+        policy: LRConflictPolicy,
+        error_non_terminals: &[&str],
+        max_errors: usize,
+    ) -> LRParseTrace {
+        let mut trace = LRParseTrace {
+            steps: Vec::new(),
+            accepted: false,
+            errors: 0,
+            terminals: self.terminals.clone(),
+        };
+
+        let mut states: Vec<usize> = vec![0];
+        let mut symbols: Vec<String> = vec![END_MARK.to_string()];
+        let input: Vec<&str> = tokens.iter().cloned().chain([END_MARK]).collect();
+        let mut pos: usize = 0;
+        // state stacks seen since the last shift, a repeat means the reductions loop
+        let mut seen: HashSet<Vec<usize>> = HashSet::new();
+        let mut recovered_at: Option<usize> = None;
+
+        loop {
+            let state = *states.last().unwrap();
+            let a = input[pos];
+            let mut step = LRParseStep {
+                states: states.clone(),
+                symbols: symbols.clone(),
+                input: input[pos..].iter().map(|s| s.to_string()).collect(),
+                action: LRParseAction::Accept,
+            };
+
+            let cell = self
+                .terminals
+                .iter()
+                .position(|t| t == a)
+                .map(|col| &self.action[state][col]);
+            let syntax_error = !matches!(cell, Some(cell) if !cell.is_empty());
+            if syntax_error && trace.errors < max_errors {
+                let repeated = recovered_at == Some(pos);
+                if let Some(recovery) = self.recover(
+                    &mut states,
+                    &mut symbols,
+                    &input,
+                    &mut pos,
+                    error_non_terminals,
+                    repeated,
+                ) {
+                    recovered_at = Some(pos);
+                    trace.errors += 1;
+                    seen.clear();
+                    step.action = LRParseAction::Recover(recovery);
+                    trace.steps.push(step);
+                    continue;
+                }
+            }
+
+            let chosen = match cell {
+                None => Err(format!("unknown terminal {}", a)),
+                Some(cell) if cell.is_empty() => Err(format!("empty ACTION[{}, {}]", state, a)),
+                Some(cell) if cell.len() == 1 => Ok(&cell[0]),
+                Some(cell) => match policy {
+                    LRConflictPolicy::Refuse => {
+                        Err(format!("conflict at ACTION[{}, {}]", state, a))
+                    }
+                    LRConflictPolicy::PreferShift => Ok(cell
+                        .iter()
+                        .find(|action| matches!(action, LRParsingTableAction::Shift(_)))
+                        .unwrap_or(&cell[0])),
+                },
+            };
+
+            step.action = match chosen {
+                Err(e) => LRParseAction::Error(e),
+                Ok(LRParsingTableAction::Accept) => {
+                    trace.accepted = trace.errors == 0;
+                    LRParseAction::Accept
+                }
+                Ok(LRParsingTableAction::Shift(next)) => {
+                    states.push(*next);
+                    symbols.push(a.to_string());
+                    pos += 1;
+                    seen.clear();
+                    LRParseAction::Shift(*next)
+                }
+                Ok(LRParsingTableAction::Reduce((left, right))) => {
+                    let n = right.iter().filter(|s| *s != EPSILON).count();
+                    states.truncate(states.len() - n);
+                    symbols.truncate(symbols.len() - n);
+                    let col = self.non_terminals.iter().position(|nt| nt == left).unwrap();
+                    match self.goto[*states.last().unwrap()][col] {
+                        Some(next) => {
+                            states.push(next);
+                            symbols.push(left.clone());
+                            if seen.insert(states.clone()) {
+                                LRParseAction::Reduce(left.clone(), right.clone())
+                            } else {
+                                LRParseAction::Error(format!(
+                                    "reduce {} -> {} loops",
+                                    left,
+                                    right.join(" ")
+                                ))
+                            }
+                        }
+                        None => LRParseAction::Error(format!(
+                            "empty GOTO[{}, {}]",
+                            states.last().unwrap(),
+                            left
+                        )),
+                    }
+                }
+            };
+
+            let stop = matches!(step.action, LRParseAction::Accept | LRParseAction::Error(_));
+            trace.steps.push(step);
+            if stop {
+                break;
+            }
+        }
+
+        trace
+    }
+
+    fn expects(&self, state: usize, a: &str) -> bool {
+        self.terminals
+            .iter()
+            .position(|t| t == a)
+            .is_some_and(|col| !self.action[state][col].is_empty())
+    }
+
+    // returns the repair, or None when nothing applies;
+    // `repeated` means the previous repair happened at the same position
+    fn recover(
+        &self,
+        states: &mut Vec<usize>,
+        symbols: &mut Vec<String>,
+        input: &[&str],
+        pos: &mut usize,
+        error_non_terminals: &[&str],
+        repeated: bool,
+    ) -> Option<LRRecovery> {
+        let state = *states.last().unwrap();
+        let a = input[*pos];
+        if !self.terminals.iter().any(|t| t == a) {
+            *pos += 1;
+            return Some(LRRecovery::Skip(a.to_string()));
+        }
+
+        if !repeated {
+            for (col, t) in self.terminals.iter().enumerate() {
+                if let [LRParsingTableAction::Shift(next)] = self.action[state][col][..] {
+                    if t != END_MARK && self.expects(next, a) {
+                        states.push(next);
+                        symbols.push(t.clone());
+                        return Some(LRRecovery::Insert(t.clone()));
+                    }
+                }
+            }
+        }
+
+        // a second repair at the same position has to discard something
+        let min_skip = usize::from(repeated);
+
+        for depth in (0..states.len()).rev() {
+            for &nt in error_non_terminals {
+                let col = match self.non_terminals.iter().position(|s| s == nt) {
+                    Some(col) => col,
+                    None => continue,
+                };
+                let next = match self.goto[states[depth]][col] {
+                    Some(next) => next,
+                    None => continue,
+                };
+                let skip = input[*pos..]
+                    .iter()
+                    .skip(min_skip)
+                    .position(|t| self.expects(next, t))
+                    .map(|skip| skip + min_skip);
+                if let Some(skip) = skip {
+                    let popped = states.len() - depth - 1;
+                    states.truncate(depth + 1);
+                    symbols.truncate(depth + 1);
+                    states.push(next);
+                    symbols.push(nt.to_string());
+                    *pos += skip;
+                    return Some(LRRecovery::Pop(popped, skip, nt.to_string()));
+                }
+            }
+        }
+        None
+    }
+}
@@ -1,10 +1,26 @@
+pub mod chomsky_normal_form;
+pub mod classify;
+pub mod conflict;
+pub mod cyk;
+pub mod derivation;
+pub mod earley;
+pub mod eliminate_epsilon_productions;
 pub mod eliminate_left_recursion;
+pub mod eliminate_unit_productions;
+pub mod glr;
+#[allow(clippy::module_inception)]
 pub mod grammar;
+pub mod greibach_normal_form;
+pub mod left_factoring;
+pub mod ll1_parse;
 pub mod ll1_parsing_table;
 pub mod lr_fsm;
+pub mod lr_parse;
 pub mod nullable_first_follow;
 pub mod parse;
+pub mod parse_tree;
 pub mod pretty_print;
+pub mod useless_symbols;
 pub use grammar::Grammar;
 
 pub const EPSILON: &str = "ϵ";
@@ -84,9 +84,7 @@ impl Grammar {
                         nt.productions
                             .iter()
                             .fold(HashSet::new(), |mut first, production| {
-                                first.extend(
-                                    self.calculate_first_for_production(production).into_iter(),
-                                );
+                                first.extend(self.calculate_first_for_production(production));
                                 first
                             })
                     }
@@ -101,7 +99,7 @@ impl Grammar {
         }
     }
 
-    pub fn calculate_follow_for_production(&self, production: &Vec<usize>) -> HashSet<usize> {
+    pub fn calculate_follow_for_production(&self, production: &[usize]) -> HashSet<usize> {
         let mut follow = HashSet::new();
         for idx in production.iter().rev() {
             match &self.symbols[*idx] {
@@ -0,0 +1,224 @@
+use crowbook_text_processing::escape;
+use serde::Serialize;
+
+use super::{
+    ll1_parse::{LL1ParseAction, LL1ParseTrace},
+    lr_parse::{LRParseAction, LRParseTrace},
+    pretty_print::{dot_escape, mermaid_escape, plantuml_escape},
+    EPSILON,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParseTree {
+    pub symbol: String,
+    pub children: Vec<ParseTree>,
+}
+
+impl ParseTree {
+    fn leaf(symbol: &str) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            children: Vec::new(),
+        }
+    }
+
+    // leaves are terminals or ϵ, every non-terminal has been expanded
+    fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    pub fn leaves(&self) -> Vec<&str> {
+        if self.is_leaf() {
+            if self.symbol == EPSILON {
+                Vec::new()
+            } else {
+                vec![self.symbol.as_str()]
+            }
+        } else {
+            self.children.iter().flat_map(|c| c.leaves()).collect()
+        }
+    }
+
+    pub fn to_plaintext(&self) -> String {
+        fn f(t: &ParseTree, prefix: &str, last: bool, root: bool, lines: &mut Vec<String>) {
+            if root {
+                lines.push(t.symbol.clone());
+            } else {
+                let branch = if last { "└── " } else { "├── " };
+                lines.push(format!("{}{}{}", prefix, branch, t.symbol));
+            }
+            let prefix = if root {
+                String::new()
+            } else {
+                format!("{}{}", prefix, if last { "    " } else { "│   " })
+            };
+            for (i, c) in t.children.iter().enumerate() {
+                f(c, &prefix, i + 1 == t.children.len(), false, lines);
+            }
+        }
+
+        let mut lines: Vec<String> = Vec::new();
+        f(self, "", true, true, &mut lines);
+        lines.join("\n")
+    }
+
+    fn label_to_latex(&self) -> String {
+        if self.symbol == EPSILON {
+            "\\epsilon".to_string()
+        } else if self.is_leaf() {
+            format!("\\text{{{}}}", escape::tex(self.symbol.as_str()))
+        } else {
+            escape::tex(self.symbol.as_str()).to_string()
+        }
+    }
+
+    pub fn to_latex(&self) -> String {
+        fn f(t: &ParseTree, depth: usize, out: &mut String) {
+            out.push_str(&"  ".repeat(depth));
+            out.push_str(&format!("[{{${}$}}", t.label_to_latex()));
+            if t.is_leaf() {
+                out.push(']');
+                return;
+            }
+            for c in &t.children {
+                out.push('\n');
+                f(c, depth + 1, out);
+            }
+            out.push(']');
+        }
+
+        let mut out = String::new();
+        f(self, 0, &mut out);
+        "\\begin{forest}\n".to_string() + &out + "\n\\end{forest}"
+    }
+
+    pub fn to_dot(&self) -> String {
+        fn f(t: &ParseTree, id: &mut usize, lines: &mut Vec<String>) -> usize {
+            let me = *id;
+            *id += 1;
+            let label = dot_escape(&t.symbol);
+            if t.is_leaf() && t.symbol != EPSILON {
+                lines.push(format!("  n{} [label=\"{}\", shape=box];", me, label));
+            } else {
+                lines.push(format!("  n{} [label=\"{}\"];", me, label));
+            }
+            for c in &t.children {
+                let child = f(c, id, lines);
+                lines.push(format!("  n{} -> n{};", me, child));
+            }
+            me
+        }
+
+        let mut lines: Vec<String> = Vec::new();
+        f(self, &mut 0, &mut lines);
+        "digraph {\n  ordering=out;\n  node [shape=plaintext];\n".to_string()
+            + &lines.join("\n")
+            + "\n}"
+    }
+
+    // terminals are drawn as boxes, non-terminals and ϵ as rounded boxes
+    pub fn to_mermaid(&self) -> String {
+        fn f(t: &ParseTree, id: &mut usize, lines: &mut Vec<String>) -> usize {
+            let me = *id;
+            *id += 1;
+            let label = mermaid_escape(&t.symbol);
+            if t.is_leaf() && t.symbol != EPSILON {
+                lines.push(format!("    n{}[\"{}\"]", me, label));
+            } else {
+                lines.push(format!("    n{}(\"{}\")", me, label));
+            }
+            for c in &t.children {
+                let child = f(c, id, lines);
+                lines.push(format!("    n{} --> n{}", me, child));
+            }
+            me
+        }
+
+        let mut lines: Vec<String> = vec!["flowchart TD".to_string()];
+        f(self, &mut 0, &mut lines);
+        lines.join("\n")
+    }
+
+    // a work breakdown structure, leaves without boxes
+    pub fn to_plantuml(&self) -> String {
+        fn f(t: &ParseTree, depth: usize, lines: &mut Vec<String>) {
+            let stars = "*".repeat(depth + 1);
+            if t.is_leaf() {
+                lines.push(format!("{}_ {}", stars, plantuml_escape(&t.symbol)));
+            } else {
+                lines.push(format!("{} {}", stars, plantuml_escape(&t.symbol)));
+            }
+            for c in &t.children {
+                f(c, depth + 1, lines);
+            }
+        }
+
+        let mut lines: Vec<String> = vec!["@startwbs".to_string()];
+        f(self, 0, &mut lines);
+        lines.push("@endwbs".to_string());
+        lines.join("\n")
+    }
+}
+
+impl LL1ParseTrace {
+    pub fn to_parse_tree(&self) -> Result<ParseTree, String> {
+        if !self.accepted {
+            return Err("input string is not accepted".to_string());
+        }
+
+        // predictions come in leftmost order, so the tree is built in preorder
+        fn f<'a>(
+            symbol: &str,
+            predictions: &mut impl Iterator<Item = &'a Vec<String>>,
+            terminals: &[String],
+        ) -> ParseTree {
+            if symbol == EPSILON || terminals.iter().any(|t| t == symbol) {
+                return ParseTree::leaf(symbol);
+            }
+            let right = predictions.next().unwrap();
+            ParseTree {
+                symbol: symbol.to_string(),
+                children: right.iter().map(|s| f(s, predictions, terminals)).collect(),
+            }
+        }
+
+        let mut predictions = self.steps.iter().filter_map(|step| match &step.action {
+            LL1ParseAction::Predict(_, right) => Some(right),
+            _ => None,
+        });
+        Ok(f(
+            self.steps[0].stack.last().unwrap(),
+            &mut predictions,
+            &self.terminals,
+        ))
+    }
+}
+
+impl LRParseTrace {
+    pub fn to_parse_tree(&self) -> Result<ParseTree, String> {
+        if !self.accepted {
+            return Err("input string is not accepted".to_string());
+        }
+
+        let mut stack: Vec<ParseTree> = Vec::new();
+        for step in &self.steps {
+            match &step.action {
+                LRParseAction::Shift(_) => stack.push(ParseTree::leaf(&step.input[0])),
+                LRParseAction::Reduce(left, right) => {
+                    let n = right.iter().filter(|s| *s != EPSILON).count();
+                    let children = if n == 0 {
+                        vec![ParseTree::leaf(EPSILON)]
+                    } else {
+                        stack.split_off(stack.len() - n)
+                    };
+                    stack.push(ParseTree {
+                        symbol: left.clone(),
+                        children,
+                    });
+                }
+                _ => {}
+            }
+        }
+        Ok(stack.pop().unwrap())
+    }
+}
@@ -2,12 +2,12 @@ use std::collections::HashSet;
 
 use super::{
     lr_fsm::{DotProduction, LRItem, LRParsingTable, LRParsingTableAction, LRFSM},
-    Grammar, EPSILON,
+    Grammar, END_MARK, EPSILON,
 };
 use crowbook_text_processing::escape;
 use serde::Serialize;
 
-fn production_right_to_latex<'a>(
+pub(super) fn production_right_to_latex<'a>(
     production: impl Iterator<Item = &'a str>,
     terminal_set: &HashSet<&str>,
 ) -> String {
@@ -24,6 +24,96 @@ fn production_right_to_latex<'a>(
         .replace(super::EPSILON, "\\epsilon")
 }
 
+// rows may be shorter than `right_aligned`, which gives the number of columns
+pub(super) fn table_to_plaintext(rows: &[Vec<String>], right_aligned: &[bool]) -> String {
+    let width: Vec<usize> = (0..right_aligned.len())
+        .map(|j| {
+            rows.iter()
+                .filter_map(|row| row.get(j))
+                .map(|s| s.chars().count())
+                .max()
+                .unwrap()
+        })
+        .collect();
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .enumerate()
+                .map(|(j, s)| {
+                    if right_aligned[j] {
+                        format!("{:>width$}", s, width = width[j])
+                    } else {
+                        format!("{:<width$}", s, width = width[j])
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" | ")
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// a GitHub-flavored table, the first row is the header
+pub(super) fn table_to_markdown(rows: &[Vec<String>]) -> String {
+    let line = |row: &Vec<String>| {
+        format!(
+            "| {} |",
+            row.iter()
+                .map(|s| s.replace('|', "\\|"))
+                .collect::<Vec<_>>()
+                .join(" | ")
+        )
+    };
+    let mut output: Vec<String> = vec![line(&rows[0])];
+    output.push(format!("|{}", " --- |".repeat(rows[0].len())));
+    output.extend(rows[1..].iter().map(line));
+    output.join("\n")
+}
+
+// the first row is the header, cells are already escaped
+pub(super) fn table_to_html(rows: &[Vec<String>]) -> String {
+    let line = |row: &Vec<String>, tag: &str| {
+        format!(
+            "<tr>{}</tr>",
+            row.iter()
+                .map(|s| format!("<{}>{}</{}>", tag, s, tag))
+                .collect::<String>()
+        )
+    };
+    let mut output: Vec<String> = vec!["<table>".to_string(), line(&rows[0], "th")];
+    output.extend(rows[1..].iter().map(|row| line(row, "td")));
+    output.push("</table>".to_string());
+    output.join("\n")
+}
+
+// a Typst string literal, usable wherever content is expected
+pub(super) fn typst_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// the first row is the header, cells are Typst expressions
+pub(super) fn table_to_typst(rows: &[Vec<String>]) -> String {
+    let mut output: Vec<String> = vec![
+        "#table(".to_string(),
+        format!("  columns: {},", rows[0].len()),
+        format!("  table.header({}),", rows[0].join(", ")),
+    ];
+    output.extend(rows[1..].iter().map(|row| format!("  {},", row.join(", "))));
+    output.push(")".to_string());
+    output.join("\n")
+}
+
+pub(super) fn conflict_to_typst(s: &str) -> String {
+    format!("text(fill: red, {})", typst_string(s))
+}
+
+// the report stylesheet colors these red
+pub(super) fn conflict_to_html(s: &str) -> String {
+    format!("<span class=\"conflict\">{}</span>", s)
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct ProductionOutput<'a> {
     pub left: &'a str,
@@ -51,7 +141,7 @@ impl ProductionOutput<'_> {
             .join(if multiline { "\n" } else { "" })
     }
     pub fn to_latex(&self, and_sign: bool, terminal_set: &HashSet<&str>) -> String {
-        if self.rights.len() == 0 {
+        if self.rights.is_empty() {
             return String::new();
         }
 
@@ -97,17 +187,64 @@ impl ProductionOutputVec<'_> {
             .collect::<Vec<String>>()
             .join("\\\\\n")
     }
+
+    pub fn to_markdown(&self) -> String {
+        let mut rows: Vec<Vec<String>> = vec![vec!["Left".to_string(), "Right".to_string()]];
+        for p in &self.productions {
+            rows.push(vec![
+                p.left.to_string(),
+                p.rights
+                    .iter()
+                    .map(|right| right.join(" "))
+                    .collect::<Vec<_>>()
+                    .join(" | "),
+            ]);
+        }
+        table_to_markdown(&rows)
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut rows: Vec<Vec<String>> = vec![vec!["Left".to_string(), "Right".to_string()]];
+        for p in &self.productions {
+            rows.push(vec![
+                escape::html(p.left).to_string(),
+                p.rights
+                    .iter()
+                    .map(|right| escape::html(right.join(" ")).to_string())
+                    .collect::<Vec<_>>()
+                    .join(" | "),
+            ]);
+        }
+        table_to_html(&rows)
+    }
+
+    pub fn to_typst(&self) -> String {
+        let mut rows: Vec<Vec<String>> = vec![vec![typst_string("Left"), typst_string("Right")]];
+        for p in &self.productions {
+            rows.push(vec![
+                typst_string(p.left),
+                typst_string(
+                    &p.rights
+                        .iter()
+                        .map(|right| right.join(" "))
+                        .collect::<Vec<_>>()
+                        .join(" | "),
+                ),
+            ]);
+        }
+        table_to_typst(&rows)
+    }
 }
 
 impl Grammar {
-    pub fn to_production_output_vec(&self) -> ProductionOutputVec {
+    pub fn to_production_output_vec(&self) -> ProductionOutputVec<'_> {
         let mut productions = Vec::new();
         for symbol in self.symbols.iter().skip(1) {
             // skip(1): skip epsilon
             if let Some(non_terminal) = symbol.non_terminal() {
                 let mut rights = Vec::new();
                 for production in &non_terminal.productions {
-                    rights.push(self.production_to_vec_str(&production));
+                    rights.push(self.production_to_vec_str(production));
                 }
                 productions.push(ProductionOutput {
                     left: non_terminal.name.as_str(),
@@ -186,10 +323,59 @@ impl NonTerminalOutputVec<'_> {
             + &content
             + "\\\\\n\\end{tabular}"
     }
+    pub fn to_markdown(&self) -> String {
+        let mut rows: Vec<Vec<String>> = vec![vec![
+            "Symbol".to_string(),
+            "Nullable".to_string(),
+            "First".to_string(),
+            "Follow".to_string(),
+        ]];
+        for e in &self.non_terminals {
+            rows.push(vec![
+                e.name.to_string(),
+                e.nullable.to_string(),
+                e.first.join(", "),
+                e.follow.join(", "),
+            ]);
+        }
+        table_to_markdown(&rows)
+    }
+    pub fn to_html(&self) -> String {
+        let mut rows: Vec<Vec<String>> = vec![vec![
+            "Symbol".to_string(),
+            "Nullable".to_string(),
+            "First".to_string(),
+            "Follow".to_string(),
+        ]];
+        for e in &self.non_terminals {
+            rows.push(vec![
+                escape::html(e.name).to_string(),
+                e.nullable.to_string(),
+                escape::html(e.first.join(", ")).to_string(),
+                escape::html(e.follow.join(", ")).to_string(),
+            ]);
+        }
+        table_to_html(&rows)
+    }
+    pub fn to_typst(&self) -> String {
+        let mut rows: Vec<Vec<String>> = vec![["Symbol", "Nullable", "First", "Follow"]
+            .iter()
+            .map(|s| typst_string(s))
+            .collect()];
+        for e in &self.non_terminals {
+            rows.push(vec![
+                typst_string(e.name),
+                typst_string(&e.nullable.to_string()),
+                typst_string(&e.first.join(", ")),
+                typst_string(&e.follow.join(", ")),
+            ]);
+        }
+        table_to_typst(&rows)
+    }
 }
 
 impl Grammar {
-    pub fn to_non_terminal_output_vec(&mut self) -> NonTerminalOutputVec {
+    pub fn to_non_terminal_output_vec(&mut self) -> NonTerminalOutputVec<'_> {
         if !self.is_nullable_first_follow_valid() {
             self.calculate_nullable_first_follow();
         }
@@ -235,16 +421,16 @@ impl DotProduction {
         output.push_str(" -> ");
         for (i, s) in self.production.iter().enumerate() {
             if i != 0 {
-                output.push_str(" ");
+                output.push(' ');
             }
 
             if i == self.position {
-                output.push_str(".");
+                output.push('.');
             }
             output.push_str(s);
         }
         if self.position == self.production.len() {
-            output.push_str(".");
+            output.push('.');
         }
         if let Some(lookahead) = &self.lookahead {
             output.push_str(", ");
@@ -271,7 +457,7 @@ impl DotProduction {
         if let Some(lookahead) = &self.lookahead {
             let lookahead = lookahead
                 .iter()
-                .map(|s| escape::tex(s))
+                .map(escape::tex)
                 .collect::<Vec<_>>()
                 .join(" ");
             format!("${} \\rightarrow {}$, {}", self.left, right, lookahead)
@@ -290,7 +476,7 @@ impl LRItem {
             .collect::<Vec<_>>()
             .join("\n");
 
-        let extend = if self.extend.len() > 0 {
+        let extend = if !self.extend.is_empty() {
             format!(
                 "\n---\n{}",
                 self.extend
@@ -303,7 +489,7 @@ impl LRItem {
             String::new()
         };
 
-        let edges = if self.edges.len() > 0 || is_end {
+        let edges = if !self.edges.is_empty() || is_end {
             format!(
                 "\n===\n{}",
                 self.edges
@@ -320,7 +506,7 @@ impl LRItem {
         format!("{}{}{}", kernel, extend, edges)
     }
 
-    pub fn node_to_latex(&self, id: usize, terminal_set: &HashSet<&str>) -> String {
+    pub fn node_to_latex(&self, id: usize, at: (f64, f64), terminal_set: &HashSet<&str>) -> String {
         let content = self
             .kernel
             .iter()
@@ -329,39 +515,92 @@ impl LRItem {
             .collect::<Vec<_>>()
             .join(" \\\\ \n");
         format!(
-            "\\node [block] (I_{}){}\n{{\n$I_{}$\\\\\n{}\n}};",
+            "\\node [block] (I_{}) at {}\n{{\n$I_{}$\\\\\n{}\n}};",
             id,
-            if id > 0 {
-                if id % 2 == 0 {
-                    format!(" [below of = I_{}] ", id - 2)
-                } else {
-                    format!(" [right of = I_{}] ", id - 1)
-                }
-            } else {
-                String::new()
-            },
+            coordinate_to_latex(at),
             id,
             content
         )
     }
 
-    pub fn edge_to_latex(&self, id: usize) -> String {
+    // a record with the state name, the kernel items and the closure items
+    pub fn node_to_dot(&self, id: usize) -> String {
+        let items = |items: &[DotProduction]| {
+            items
+                .iter()
+                .map(|c| record_escape(&c.to_plaintext()) + "\\l")
+                .collect::<String>()
+        };
+        let mut fields = vec![format!("I{}", id), items(&self.kernel)];
+        if !self.extend.is_empty() {
+            fields.push(items(&self.extend));
+        }
+        format!("  I{} [label=\"{{{}}}\"];", id, fields.join("|"))
+    }
+
+    pub fn edge_to_dot(&self, id: usize) -> String {
         self.edges
             .iter()
-            .map(|(e, v)| {
-                format!(
-                    "\\path [->] (I_{}) edge {} node [above]{{{}}} (I_{});",
-                    id,
-                    if id == *v { "[loop left]" } else { "[right]" },
-                    e,
-                    v
-                )
-            })
+            .map(|(e, v)| format!("  I{} -> I{} [label=\"{}\"];", id, v, dot_escape(e)))
             .collect::<Vec<_>>()
             .join("\n")
     }
 }
 
+// DOT labels are double-quoted strings
+pub(super) fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// Mermaid labels take HTML entity codes
+pub(super) fn mermaid_escape(s: &str) -> String {
+    s.replace('#', "#35;")
+        .replace('"', "#quot;")
+        .replace(':', "#58;")
+        .replace(';', "#59;")
+}
+
+// creole markup and the diagram syntax both use punctuation, so all of it but
+// the few symbols items are made of becomes a unicode escape
+pub(super) fn plantuml_escape(s: &str) -> String {
+    let mut output = String::new();
+    for c in s.chars() {
+        if c.is_ascii_punctuation() && !matches!(c, '.' | '\'' | '(' | ')' | ',') {
+            output += &format!("<U+{:04X}>", c as u32);
+        } else {
+            output.push(c);
+        }
+    }
+    output
+}
+
+// record labels also treat braces, bars and angle brackets specially
+fn record_escape(s: &str) -> String {
+    let mut output = String::new();
+    for c in s.chars() {
+        if "\\\"{}|<>".contains(c) {
+            output.push('\\');
+        }
+        output.push(c);
+    }
+    output
+}
+
+// rounded to avoid printing -0.00
+fn coordinate_to_latex(at: (f64, f64)) -> String {
+    let round = |v: f64| (v * 100.0).round() / 100.0 + 0.0;
+    format!("({:.2}, {:.2})", round(at.0), round(at.1))
+}
+
+struct LRFSMLayout {
+    layer: Vec<usize>,
+    // index inside the layer, top to bottom
+    position: Vec<usize>,
+    loop_above: Vec<bool>,
+    // centers in cm, the accept node last
+    at: Vec<(f64, f64)>,
+}
+
 impl LRFSM {
     pub fn to_plaintext(&self) -> String {
         let states = self
@@ -375,19 +614,387 @@ impl LRFSM {
         states
     }
 
+    // Layered drawing: layers are BFS depths from the start state, ordered by
+    // barycenter sweeps to reduce crossings. Edges to the next layer are straight,
+    // the others bend (forward ones above, back ones below).
+    fn layout(&self) -> LRFSMLayout {
+        // the accept node is the last vertex
+        let n = self.states.len() + 1;
+        let accept = n - 1;
+        let mut adj: Vec<Vec<usize>> = self
+            .states
+            .iter()
+            .map(|s| s.edges.values().cloned().collect())
+            .collect();
+        adj.push(Vec::new());
+        adj[self.end].push(accept);
+
+        let mut layer: Vec<Option<usize>> = vec![None; n];
+        let mut discovered: Vec<usize> = vec![self.start];
+        layer[self.start] = Some(0);
+        let mut k = 0;
+        while k < discovered.len() {
+            let u = discovered[k];
+            for &v in &adj[u] {
+                if layer[v].is_none() {
+                    layer[v] = Some(layer[u].unwrap() + 1);
+                    discovered.push(v);
+                }
+            }
+            k += 1;
+        }
+        let depth = layer.iter().flatten().max().unwrap() + 1;
+        for (u, l) in layer.iter_mut().enumerate() {
+            if l.is_none() {
+                *l = Some(depth);
+                discovered.push(u);
+            }
+        }
+        let layer: Vec<usize> = layer.into_iter().map(|l| l.unwrap()).collect();
+
+        let mut neighbours: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for (u, vs) in adj.iter().enumerate() {
+            for &v in vs {
+                if u != v {
+                    neighbours[u].push(v);
+                    neighbours[v].push(u);
+                }
+            }
+        }
+
+        let mut layers: Vec<Vec<usize>> = vec![Vec::new(); layer.iter().max().unwrap() + 1];
+        for &u in &discovered {
+            layers[layer[u]].push(u);
+        }
+        let mut position: Vec<usize> = vec![0; n];
+        let renumber = |layers: &Vec<Vec<usize>>, position: &mut Vec<usize>| {
+            for l in layers {
+                for (i, &u) in l.iter().enumerate() {
+                    position[u] = i;
+                }
+            }
+        };
+        renumber(&layers, &mut position);
+
+        for _ in 0..4 {
+            let down: Vec<(usize, usize)> = (1..layers.len()).map(|l| (l, l - 1)).collect();
+            let up: Vec<(usize, usize)> = (0..layers.len() - 1).rev().map(|l| (l, l + 1)).collect();
+            for (l, fixed) in down.into_iter().chain(up) {
+                let barycenter = |u: usize| {
+                    let ps: Vec<usize> = neighbours[u]
+                        .iter()
+                        .filter(|&&v| layer[v] == fixed)
+                        .map(|&v| position[v])
+                        .collect();
+                    if ps.is_empty() {
+                        position[u] as f64
+                    } else {
+                        ps.iter().sum::<usize>() as f64 / ps.len() as f64
+                    }
+                };
+                let mut keyed: Vec<(f64, usize)> =
+                    layers[l].iter().map(|&u| (barycenter(u), u)).collect();
+                keyed.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+                layers[l] = keyed.into_iter().map(|(_, u)| u).collect();
+                renumber(&layers, &mut position);
+            }
+        }
+
+        // the horizontal sides are taken by the edges between layers,
+        // so loops go above, or below for the last of several nodes in a layer
+        let loop_above: Vec<bool> = (0..n)
+            .map(|u| position[u] == 0 || position[u] + 1 < layers[layer[u]].len())
+            .collect();
+
+        // stack the nodes of a layer by their height (in cm), centered on y = 0,
+        // with room for the loops
+        let room = |u: usize| if adj[u].contains(&u) { 1.0 } else { 0.0 };
+        let height = |u: usize| {
+            if u == accept {
+                0.6
+            } else {
+                let s = &self.states[u];
+                0.5 * (s.kernel.len() + s.extend.len() + 1) as f64 + 0.4
+            }
+        };
+        let mut at: Vec<(f64, f64)> = vec![(0.0, 0.0); n];
+        for (l, nodes) in layers.iter().enumerate() {
+            let total: f64 = nodes.iter().map(|&u| height(u) + room(u)).sum::<f64>()
+                + 0.8 * (nodes.len() - 1) as f64;
+            let mut top = total / 2.0;
+            for &u in nodes {
+                let above = if loop_above[u] { room(u) } else { 0.0 };
+                at[u] = (5.0 * l as f64, top - above - height(u) / 2.0);
+                top -= height(u) + room(u) + 0.8;
+            }
+        }
+
+        LRFSMLayout {
+            layer,
+            position,
+            loop_above,
+            at,
+        }
+    }
+
     pub fn to_latex(&self) -> String {
         let terminal_set: HashSet<&str> = self.terminals.iter().map(|s| s.as_str()).collect();
-        format!(
-            "\\begin{{tikzpicture}}[node distance=5cm,block/.style={{state, rectangle, text width=6em}}]\n{}\n\\node (accept) [right of = I_1] {{accept}};\n\\path [->] (I_{}) edge [right] node [above right]{{\\$}} (accept);\n\\end{{tikzpicture}}",
-            self.states
+        let layout = self.layout();
+        let accept = self.states.len();
+
+        let mut lines: Vec<String> = self
+            .states
+            .iter()
+            .enumerate()
+            .map(|(i, s)| s.node_to_latex(i, layout.at[i], &terminal_set))
+            .collect();
+        lines.push(format!(
+            "\\node (accept) at {} {{accept}};",
+            coordinate_to_latex(layout.at[accept])
+        ));
+
+        for (u, s) in self.states.iter().enumerate() {
+            for (e, &v) in &s.edges {
+                let label = escape::tex(e.as_str());
+                let edge = if u == v {
+                    let side = if layout.loop_above[u] {
+                        "above"
+                    } else {
+                        "below"
+                    };
+                    format!("edge [loop {}] node [{}]{{{}}}", side, side, label)
+                } else if layout.layer[v] == layout.layer[u] + 1 {
+                    format!("edge node [above]{{{}}}", label)
+                } else if layout.layer[v] >= layout.layer[u] {
+                    format!("edge [bend left=20] node [auto]{{{}}}", label)
+                } else {
+                    format!("edge [bend left=30] node [auto]{{{}}}", label)
+                };
+                lines.push(format!("\\path [->] (I_{}) {} (I_{});", u, edge, v));
+            }
+        }
+        lines.push(format!(
+            "\\path [->] (I_{}) edge node [above]{{\\$}} (accept);",
+            self.end
+        ));
+
+        "\\begin{tikzpicture}[block/.style={state, rectangle, text width=6em}]\n".to_string()
+            + &lines.join("\n")
+            + "\n\\end{tikzpicture}"
+    }
+
+    // a fletcher diagram on the same layered layout as the TikZ picture
+    pub fn to_typst(&self) -> String {
+        let layout = self.layout();
+        let accept = self.states.len();
+        let at = |u: usize| format!("({}, {})", layout.layer[u], layout.position[u]);
+
+        let mut lines: Vec<String> = vec![
+            "#import \"@preview/fletcher:0.5.1\" as fletcher: diagram, node, edge".to_string(),
+            "#diagram(".to_string(),
+            "  node-stroke: 0.5pt,".to_string(),
+            "  spacing: (3em, 2em),".to_string(),
+        ];
+        for (i, s) in self.states.iter().enumerate() {
+            let items = |items: &[DotProduction]| {
+                items
+                    .iter()
+                    .map(|c| format!("#{}", typst_string(&c.to_plaintext())))
+                    .collect::<Vec<_>>()
+                    .join(" \\ ")
+            };
+            let mut label = format!("*I{}* \\ {}", i, items(&s.kernel));
+            if !s.extend.is_empty() {
+                label += " #line(length: 100%, stroke: 0.5pt) ";
+                label += &items(&s.extend);
+            }
+            lines.push(format!("  node({}, align(left)[{}]),", at(i), label));
+        }
+        lines.push(format!("  node({}, [accept], stroke: none),", at(accept)));
+
+        for (u, s) in self.states.iter().enumerate() {
+            for (e, &v) in &s.edges {
+                let bend = if u == v {
+                    if layout.loop_above[u] {
+                        ", bend: 130deg"
+                    } else {
+                        ", bend: -130deg"
+                    }
+                } else if layout.layer[v] == layout.layer[u] + 1 {
+                    ""
+                } else if layout.layer[v] >= layout.layer[u] {
+                    ", bend: 20deg"
+                } else {
+                    ", bend: 30deg"
+                };
+                lines.push(format!(
+                    "  edge({}, {}, \"->\", [#{}]{}),",
+                    at(u),
+                    at(v),
+                    typst_string(e),
+                    bend
+                ));
+            }
+        }
+        lines.push(format!(
+            "  edge({}, {}, \"->\", [#{}]),",
+            at(self.end),
+            at(accept),
+            typst_string(END_MARK)
+        ));
+        lines.push(")".to_string());
+        lines.join("\n")
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut lines: Vec<String> = vec![
+            "digraph {".to_string(),
+            "  rankdir=LR;".to_string(),
+            "  node [shape=record];".to_string(),
+        ];
+        for (i, s) in self.states.iter().enumerate() {
+            lines.push(s.node_to_dot(i));
+        }
+        for (i, s) in self.states.iter().enumerate() {
+            if !s.edges.is_empty() {
+                lines.push(s.edge_to_dot(i));
+            }
+        }
+        lines.push("  accept [shape=plaintext];".to_string());
+        lines.push(format!("  I{} -> accept [label=\"$\"];", self.end));
+        lines.push("}".to_string());
+        lines.join("\n")
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let items = |items: &[DotProduction]| {
+            items
                 .iter()
-                .enumerate()
-                .map(|(i, s)| s.node_to_latex(i, &terminal_set))
-                .chain(self.states.iter().enumerate().map(|(i,s)| s.edge_to_latex(i)))
+                .map(|c| format!("- `{}`", c.to_plaintext()))
                 .collect::<Vec<_>>()
-                .join("\n"),
-                self.end
-        )
+                .join("\n")
+        };
+        self.states
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                let mut output = format!("**I{}**\n\n{}", i, items(&s.kernel));
+                if !s.extend.is_empty() {
+                    output += "\n\nclosure:\n\n";
+                    output += &items(&s.extend);
+                }
+                let mut edges: Vec<String> = s
+                    .edges
+                    .iter()
+                    .map(|(e, v)| format!("- `{}` → I{}", e, v))
+                    .collect();
+                if i == self.end {
+                    edges.push(format!("- `{}` → accept", END_MARK));
+                }
+                if !edges.is_empty() {
+                    output += "\n\ngoto:\n\n";
+                    output += &edges.join("\n");
+                }
+                output
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    // one row per state
+    pub fn to_html(&self) -> String {
+        let items = |items: &[DotProduction]| {
+            items
+                .iter()
+                .map(|c| escape::html(c.to_plaintext()).to_string())
+                .collect::<Vec<_>>()
+                .join("<br>")
+        };
+        let mut rows: Vec<Vec<String>> = vec![vec![
+            "State".to_string(),
+            "Kernel".to_string(),
+            "Closure".to_string(),
+            "Goto".to_string(),
+        ]];
+        for (i, s) in self.states.iter().enumerate() {
+            let mut edges: Vec<String> = s
+                .edges
+                .iter()
+                .map(|(e, v)| format!("{} → I{}", escape::html(e.as_str()), v))
+                .collect();
+            if i == self.end {
+                edges.push(format!("{} → accept", END_MARK));
+            }
+            rows.push(vec![
+                format!("I{}", i),
+                items(&s.kernel),
+                items(&s.extend),
+                edges.join("<br>"),
+            ]);
+        }
+        table_to_html(&rows)
+    }
+
+    pub fn to_mermaid(&self) -> String {
+        let mut lines: Vec<String> = vec!["stateDiagram-v2".to_string()];
+        for (i, s) in self.states.iter().enumerate() {
+            for item in s.kernel.iter().chain(s.extend.iter()) {
+                lines.push(format!(
+                    "    I{} : {}",
+                    i,
+                    mermaid_escape(&item.to_plaintext())
+                ));
+            }
+        }
+        lines.push("    [*] --> I0".to_string());
+        for (i, s) in self.states.iter().enumerate() {
+            for (e, v) in &s.edges {
+                lines.push(format!("    I{} --> I{} : {}", i, v, mermaid_escape(e)));
+            }
+        }
+        lines.push(format!("    I{} --> [*] : $", self.end));
+        lines.join("\n")
+    }
+
+    pub fn to_plantuml(&self) -> String {
+        let mut lines: Vec<String> = vec![
+            "@startuml".to_string(),
+            "hide empty description".to_string(),
+        ];
+        for (i, s) in self.states.iter().enumerate() {
+            lines.push(format!("state I{}", i));
+            for item in &s.kernel {
+                lines.push(format!(
+                    "I{} : {}",
+                    i,
+                    plantuml_escape(&item.to_plaintext())
+                ));
+            }
+            // separates the closure items from the kernel
+            if !s.extend.is_empty() {
+                lines.push(format!("I{} : --", i));
+            }
+            for item in &s.extend {
+                lines.push(format!(
+                    "I{} : {}",
+                    i,
+                    plantuml_escape(&item.to_plaintext())
+                ));
+            }
+        }
+        lines.push("[*] --> I0".to_string());
+        for (i, s) in self.states.iter().enumerate() {
+            for (e, v) in &s.edges {
+                lines.push(format!("I{} --> I{} : {}", i, v, plantuml_escape(e)));
+            }
+        }
+        lines.push(format!(
+            "I{} --> [*] : {}",
+            self.end,
+            plantuml_escape(END_MARK)
+        ));
+        lines.push("@enduml".to_string());
+        lines.join("\n")
     }
 }
 
@@ -468,6 +1075,110 @@ impl LRParsingTable {
             .join("\n")
     }
 
+    pub fn to_markdown(&self) -> String {
+        let mut rows: Vec<Vec<String>> = vec![std::iter::once(String::new())
+            .chain(
+                self.terminals
+                    .iter()
+                    .chain(self.non_terminals.iter())
+                    .cloned(),
+            )
+            .collect()];
+        for (i, (r1, r2)) in self.action.iter().zip(self.goto.iter()).enumerate() {
+            rows.push(
+                std::iter::once(i.to_string())
+                    .chain(r1.iter().map(|actions| {
+                        let r = actions
+                            .iter()
+                            .map(|action| action.to_plaintext())
+                            .collect::<Vec<_>>()
+                            .join("; ");
+                        // conflicts in bold
+                        if actions.len() > 1 {
+                            format!("**{}**", r)
+                        } else {
+                            r
+                        }
+                    }))
+                    .chain(
+                        r2.iter()
+                            .map(|goto| goto.map(|g| g.to_string()).unwrap_or_default()),
+                    )
+                    .collect(),
+            );
+        }
+        table_to_markdown(&rows)
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut rows: Vec<Vec<String>> = vec![std::iter::once(String::new())
+            .chain(
+                self.terminals
+                    .iter()
+                    .chain(self.non_terminals.iter())
+                    .map(|s| escape::html(s.as_str()).to_string()),
+            )
+            .collect()];
+        for (i, (r1, r2)) in self.action.iter().zip(self.goto.iter()).enumerate() {
+            rows.push(
+                std::iter::once(i.to_string())
+                    .chain(r1.iter().map(|actions| {
+                        let r = actions
+                            .iter()
+                            .map(|action| escape::html(action.to_plaintext()).to_string())
+                            .collect::<Vec<_>>()
+                            .join("<br>");
+                        if actions.len() > 1 {
+                            conflict_to_html(&r)
+                        } else {
+                            r
+                        }
+                    }))
+                    .chain(
+                        r2.iter()
+                            .map(|goto| goto.map(|g| g.to_string()).unwrap_or_default()),
+                    )
+                    .collect(),
+            );
+        }
+        table_to_html(&rows)
+    }
+
+    pub fn to_typst(&self) -> String {
+        let mut rows: Vec<Vec<String>> = vec![std::iter::once(typst_string(""))
+            .chain(
+                self.terminals
+                    .iter()
+                    .chain(self.non_terminals.iter())
+                    .map(|s| typst_string(s)),
+            )
+            .collect()];
+        for (i, (r1, r2)) in self.action.iter().zip(self.goto.iter()).enumerate() {
+            rows.push(
+                std::iter::once(typst_string(&i.to_string()))
+                    .chain(r1.iter().map(|actions| {
+                        let r = actions
+                            .iter()
+                            .map(|action| action.to_plaintext())
+                            .collect::<Vec<_>>()
+                            .join("; ");
+                        if actions.len() > 1 {
+                            conflict_to_typst(&r)
+                        } else {
+                            typst_string(&r)
+                        }
+                    }))
+                    .chain(
+                        r2.iter().map(|goto| {
+                            typst_string(&goto.map(|g| g.to_string()).unwrap_or_default())
+                        }),
+                    )
+                    .collect(),
+            );
+        }
+        table_to_typst(&rows)
+    }
+
     pub fn to_latex(&self) -> String {
         let header: String = format!(
             "\\begin{{tabular}}{{c{}}}\n & \\multicolumn{{{}}}{{c}}{{action}} & \\multicolumn{{{}}}{{|c}}{{goto}}\\\\",
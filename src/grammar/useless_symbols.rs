@@ -0,0 +1,144 @@
+use std::collections::HashSet;
+
+use crowbook_text_processing::escape;
+use serde::Serialize;
+
+use super::{grammar::Symbol, Grammar, END_MARK};
+
+#[derive(Debug, Serialize)]
+pub struct UselessSymbolReport {
+    pub non_productive: Vec<String>,
+    pub unreachable: Vec<String>,
+}
+
+impl UselessSymbolReport {
+    pub fn to_plaintext(&self) -> String {
+        format!(
+            "non-productive: {}\nunreachable: {}",
+            self.non_productive.join(", "),
+            self.unreachable.join(", ")
+        )
+    }
+
+    pub fn to_latex(&self) -> String {
+        fn f(a: &[String]) -> String {
+            a.iter()
+                .map(|s| escape::tex(s.as_str()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+
+        "\\begin{tabular}{l|l}\n".to_string()
+            + &format!("Non-productive & {}\\\\\n", f(&self.non_productive))
+            + &format!("Unreachable & {}\\\\\n", f(&self.unreachable))
+            + "\\end{tabular}"
+    }
+}
+
+impl Grammar {
+    fn find_useless_symbol_indices(&self) -> (HashSet<usize>, HashSet<usize>) {
+        // epsilon is productive
+        let mut productive: HashSet<usize> = HashSet::new();
+        productive.insert(0);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for nt in self.non_terminal_iter() {
+                if productive.contains(&nt.index) {
+                    continue;
+                }
+                let is_productive = nt.productions.iter().any(|production| {
+                    production.iter().all(|s| match &self.symbols[*s] {
+                        Symbol::Terminal(_) => true,
+                        Symbol::NonTerminal(_) => productive.contains(s),
+                    })
+                });
+                if is_productive {
+                    productive.insert(nt.index);
+                    changed = true;
+                }
+            }
+        }
+
+        let non_productive: HashSet<usize> = self
+            .non_terminal_iter()
+            .map(|nt| nt.index)
+            .filter(|idx| !productive.contains(idx))
+            .collect();
+
+        // reachability only follows productions left after removing non-productive symbols
+        let mut reachable: HashSet<usize> = HashSet::new();
+        let mut stack: Vec<usize> = self.start_symbol.into_iter().collect();
+        reachable.extend(stack.iter().cloned());
+        while let Some(u) = stack.pop() {
+            if let Symbol::NonTerminal(nt) = &self.symbols[u] {
+                for production in &nt.productions {
+                    if production.iter().any(|s| non_productive.contains(s)) {
+                        continue;
+                    }
+                    for &s in production {
+                        if reachable.insert(s) {
+                            stack.push(s);
+                        }
+                    }
+                }
+            }
+        }
+
+        let unreachable: HashSet<usize> = (1..self.symbols.len())
+            .filter(|idx| {
+                !reachable.contains(idx)
+                    && !non_productive.contains(idx)
+                    && self.get_symbol_name(*idx) != END_MARK
+            })
+            .collect();
+
+        (non_productive, unreachable)
+    }
+
+    fn to_useless_symbol_report(
+        &self,
+        non_productive: &HashSet<usize>,
+        unreachable: &HashSet<usize>,
+    ) -> UselessSymbolReport {
+        let names = |set: &HashSet<usize>| {
+            let mut v: Vec<usize> = set.iter().cloned().collect();
+            v.sort();
+            v.into_iter()
+                .map(|idx| self.get_symbol_name(idx).to_string())
+                .collect()
+        };
+        UselessSymbolReport {
+            non_productive: names(non_productive),
+            unreachable: names(unreachable),
+        }
+    }
+
+    pub fn find_useless_symbols(&self) -> UselessSymbolReport {
+        let (non_productive, unreachable) = self.find_useless_symbol_indices();
+        self.to_useless_symbol_report(&non_productive, &unreachable)
+    }
+
+    pub fn remove_useless_symbols(&mut self) -> UselessSymbolReport {
+        let (non_productive, unreachable) = self.find_useless_symbol_indices();
+        let report = self.to_useless_symbol_report(&non_productive, &unreachable);
+
+        let mut removed: HashSet<usize> = non_productive.union(&unreachable).cloned().collect();
+        // the start symbol is kept even if the language is empty
+        if let Some(start_idx) = self.start_symbol {
+            removed.remove(&start_idx);
+            self.symbols[start_idx]
+                .mut_non_terminal()
+                .unwrap()
+                .productions
+                .retain(|production| production.iter().all(|s| !non_productive.contains(s)));
+        }
+        self.remove_symbols(&removed);
+
+        // productions of the start symbol may be dropped without removing any symbol
+        self.invalidate_nullable_first_follow();
+        self.calculate_nullable_first_follow();
+
+        report
+    }
+}
@@ -5,6 +5,7 @@ use wasm_bindgen::prelude::*;
 
 mod grammar;
 pub use grammar::lr_fsm::LRFSMType;
+pub use grammar::lr_parse::LRConflictPolicy;
 pub use grammar::Grammar;
 
 #[derive(Serialize, Deserialize)]
@@ -12,6 +13,13 @@ pub struct WasmArgs {
     pub grammar: String,
     pub actions: Vec<Action>,
     pub outputs: Vec<Output>,
+    #[serde(default)]
+    pub input: Option<String>,
+    #[serde(default)]
+    pub recovery: bool,
+    // separated by spaces, every non-terminal when absent
+    #[serde(default)]
+    pub error_non_terminals: Option<String>,
 }
 
 // This function is intended to be called from JavaScript.
@@ -23,26 +31,52 @@ pub struct WasmArgs {
 //         {"NonTerminal": "JSON"},
 //         {"Production": "JSON"},
 //         {"LL1ParsingTable": "JSON"},
-//         {"LRParsingTable": ["LR0", "JSON"]}
-//     ]
+//         {"LRParsingTable": ["LR0", "JSON"]},
+//         {"LL1Parse": "JSON"}
+//     ],
+//     "input": "id + id * id",
+//     "recovery": false,
+//     "error_non_terminals": "E"
 // }
 #[wasm_bindgen]
 pub fn wasm_grammar_to_output(json: &str) -> String {
     let args: WasmArgs = serde_json::from_str(json).unwrap();
-    let result = grammar_to_output(&args.grammar, &args.actions, &args.outputs);
+    let error_non_terminals: Vec<&str> = args
+        .error_non_terminals
+        .as_deref()
+        .map_or(Vec::new(), |s| s.split_whitespace().collect());
+    let options = ParseOptions {
+        input: args.input.as_deref(),
+        recovery: args.recovery,
+        error_non_terminals: &error_non_terminals,
+    };
+    let result =
+        grammar_to_output_with_options(&args.grammar, &args.actions, &args.outputs, &options);
     serde_json::to_string(&result).unwrap()
 }
 
 #[derive(Clone, Copy, Serialize, Deserialize)]
 pub enum Action {
     EliminateLeftRecursion,
+    LeftFactor,
+    EliminateEpsilonProductions,
+    EliminateUnitProductions,
+    RemoveUselessSymbols,
+    ChomskyNormalForm,
+    GreibachNormalForm,
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum Format {
     Plain,
     LaTeX,
     JSON,
+    Dot,
+    Mermaid,
+    PlantUML,
+    Markdown,
+    Html,
+    Typst,
 }
 
 #[derive(Clone, Copy, Serialize, Deserialize)]
@@ -52,6 +86,20 @@ pub enum Output {
     LL1ParsingTable(Format),
     LRFSM(LRFSMType, Format),
     LRParsingTable(LRFSMType, Format),
+    UselessSymbols(Format),
+    UnitProductions(Format),
+    LL1Conflicts(Format),
+    Conflicts(LRFSMType, Format),
+    Classification(Format),
+    LL1Parse(Format),
+    LRParse(LRFSMType, Format),
+    LL1ParseTree(Format),
+    LRParseTree(LRFSMType, Format),
+    LeftmostDerivation(Format),
+    RightmostDerivation(LRFSMType, Format),
+    Earley(Format),
+    CYK(Format),
+    GLR(LRFSMType, Format),
 }
 
 impl Output {
@@ -62,14 +110,52 @@ impl Output {
             Output::LL1ParsingTable(format) => *format = f,
             Output::LRFSM(_, format) => *format = f,
             Output::LRParsingTable(_, format) => *format = f,
+            Output::UselessSymbols(format) => *format = f,
+            Output::UnitProductions(format) => *format = f,
+            Output::LL1Conflicts(format) => *format = f,
+            Output::Conflicts(_, format) => *format = f,
+            Output::Classification(format) => *format = f,
+            Output::LL1Parse(format) => *format = f,
+            Output::LRParse(_, format) => *format = f,
+            Output::LL1ParseTree(format) => *format = f,
+            Output::LRParseTree(_, format) => *format = f,
+            Output::LeftmostDerivation(format) => *format = f,
+            Output::RightmostDerivation(_, format) => *format = f,
+            Output::Earley(format) => *format = f,
+            Output::CYK(format) => *format = f,
+            Output::GLR(_, format) => *format = f,
         }
     }
 }
 
+#[derive(Default)]
+pub struct ParseOptions<'a> {
+    // tokens separated by spaces
+    pub input: Option<&'a str>,
+    // repair syntax errors instead of stopping at the first one
+    pub recovery: bool,
+    // where the LR recovery may resume, every non-terminal when empty
+    pub error_non_terminals: &'a [&'a str],
+}
+
+// parse trees enumerated by the Earley and GLR outputs
+const PARSE_TREE_LIMIT: usize = 10;
+// syntax errors repaired by the parsing traces before giving up
+const RECOVERY_ERROR_LIMIT: usize = 10;
+
 pub fn grammar_to_output(
     grammar: &str,
     actions: &[Action],
     outputs: &[Output],
+) -> Result<Vec<Result<String, String>>, String> {
+    grammar_to_output_with_options(grammar, actions, outputs, &ParseOptions::default())
+}
+
+pub fn grammar_to_output_with_options(
+    grammar: &str,
+    actions: &[Action],
+    outputs: &[Output],
+    options: &ParseOptions,
 ) -> Result<Vec<Result<String, String>>, String> {
     let mut ret: Vec<Result<String, String>> = Vec::new();
 
@@ -82,57 +168,373 @@ pub fn grammar_to_output(
 
     for action in actions {
         match action {
-            Action::EliminateLeftRecursion => g.eliminate_left_recursion(),
+            Action::EliminateLeftRecursion => {
+                g.eliminate_left_recursion().map_err(|e| e.to_string())?
+            }
+            Action::LeftFactor => g.left_factor(),
+            Action::EliminateEpsilonProductions => g.eliminate_epsilon_productions(),
+            Action::EliminateUnitProductions => {
+                g.eliminate_unit_productions();
+            }
+            Action::RemoveUselessSymbols => {
+                g.remove_useless_symbols();
+            }
+            Action::ChomskyNormalForm => g.to_chomsky_normal_form(),
+            Action::GreibachNormalForm => g.to_greibach_normal_form().map_err(|e| e.to_string())?,
         }
     }
 
+    let tokens: Option<Vec<&str>> = options
+        .input
+        .map(|input| input.split_whitespace().collect());
+    let no_input = || Err("input string is not provided".to_string());
+    let max_errors = if options.recovery {
+        RECOVERY_ERROR_LIMIT
+    } else {
+        0
+    };
+
     for output in outputs {
         match output {
             Output::Production(format) => {
                 let t = g.to_production_output_vec();
-                ret.push(Ok(match format {
-                    Format::Plain => t.to_plaintext(),
-                    Format::LaTeX => t.to_latex(),
-                    Format::JSON => serde_json::to_string(&t).unwrap(),
-                }));
+                ret.push(match format {
+                    Format::Plain => Ok(t.to_plaintext()),
+                    Format::LaTeX => Ok(t.to_latex()),
+                    Format::JSON => Ok(serde_json::to_string(&t).unwrap()),
+                    Format::Markdown => Ok(t.to_markdown()),
+                    Format::Html => Ok(t.to_html()),
+                    Format::Typst => Ok(t.to_typst()),
+                    f => unsupported(f),
+                });
             }
             Output::NonTerminal(format) => {
                 let t = g.to_non_terminal_output_vec();
-                ret.push(Ok(match format {
-                    Format::Plain => t.to_plaintext(),
-                    Format::LaTeX => t.to_latex(),
-                    Format::JSON => serde_json::to_string(&t).unwrap(),
-                }));
+                ret.push(match format {
+                    Format::Plain => Ok(t.to_plaintext()),
+                    Format::LaTeX => Ok(t.to_latex()),
+                    Format::JSON => Ok(serde_json::to_string(&t).unwrap()),
+                    Format::Markdown => Ok(t.to_markdown()),
+                    Format::Html => Ok(t.to_html()),
+                    Format::Typst => Ok(t.to_typst()),
+                    f => unsupported(f),
+                });
             }
             Output::LL1ParsingTable(format) => {
                 let t = g.generate_ll1_parsing_table();
-                ret.push(Ok(match format {
-                    Format::Plain => t.to_plaintext(),
-                    Format::LaTeX => t.to_latex(),
-                    Format::JSON => serde_json::to_string(&t).unwrap(),
-                }));
+                ret.push(match format {
+                    Format::Plain => Ok(t.to_plaintext()),
+                    Format::LaTeX => Ok(t.to_latex()),
+                    Format::JSON => Ok(serde_json::to_string(&t).unwrap()),
+                    Format::Markdown => Ok(t.to_markdown()),
+                    Format::Html => Ok(t.to_html()),
+                    Format::Typst => Ok(t.to_typst()),
+                    f => unsupported(f),
+                });
             }
-            Output::LRFSM(typ, format) => ret.push(g.to_lr_fsm(*typ).and_then(|t| {
-                Ok(match format {
-                    Format::Plain => t.to_plaintext(),
-                    Format::LaTeX => t.to_latex(),
-                    Format::JSON => serde_json::to_string(&t).unwrap(),
-                })
+            Output::LRFSM(typ, format) => ret.push(g.to_lr_fsm(*typ).map(|t| match format {
+                Format::Plain => t.to_plaintext(),
+                Format::LaTeX => t.to_latex(),
+                Format::JSON => serde_json::to_string(&t).unwrap(),
+                Format::Dot => t.to_dot(),
+                Format::Mermaid => t.to_mermaid(),
+                Format::PlantUML => t.to_plantuml(),
+                Format::Markdown => t.to_markdown(),
+                Format::Html => t.to_html(),
+                Format::Typst => t.to_typst(),
             })),
             Output::LRParsingTable(typ, format) => ret.push(g.to_lr_fsm(*typ).and_then(|t| {
                 let t = t.to_parsing_table();
-                Ok(match format {
-                    Format::Plain => t.to_plaintext(),
-                    Format::LaTeX => t.to_latex(),
-                    Format::JSON => serde_json::to_string(&t).unwrap(),
-                })
+                match format {
+                    Format::Plain => Ok(t.to_plaintext()),
+                    Format::LaTeX => Ok(t.to_latex()),
+                    Format::JSON => Ok(serde_json::to_string(&t).unwrap()),
+                    Format::Markdown => Ok(t.to_markdown()),
+                    Format::Html => Ok(t.to_html()),
+                    Format::Typst => Ok(t.to_typst()),
+                    f => unsupported(f),
+                }
+            })),
+            Output::UselessSymbols(format) => {
+                let t = g.find_useless_symbols();
+                ret.push(match format {
+                    Format::Plain => Ok(t.to_plaintext()),
+                    Format::LaTeX => Ok(t.to_latex()),
+                    Format::JSON => Ok(serde_json::to_string(&t).unwrap()),
+                    f => unsupported(f),
+                });
+            }
+            Output::UnitProductions(format) => {
+                // what eliminating unit productions would inline, the grammar is kept
+                let t = g.clone().eliminate_unit_productions();
+                ret.push(match format {
+                    Format::Plain => Ok(t.to_plaintext()),
+                    Format::LaTeX => Ok(t.to_latex()),
+                    Format::JSON => Ok(serde_json::to_string(&t).unwrap()),
+                    f => unsupported(f),
+                });
+            }
+            Output::LL1Conflicts(format) => {
+                let t = g.generate_ll1_parsing_table().conflicts();
+                ret.push(match format {
+                    Format::Plain => Ok(t.to_plaintext()),
+                    Format::LaTeX => Ok(t.to_latex()),
+                    Format::JSON => Ok(serde_json::to_string(&t).unwrap()),
+                    f => unsupported(f),
+                });
+            }
+            Output::Conflicts(typ, format) => ret.push(g.to_lr_fsm(*typ).and_then(|t| {
+                let t = t.to_parsing_table().conflicts();
+                match format {
+                    Format::Plain => Ok(t.to_plaintext()),
+                    Format::LaTeX => Ok(t.to_latex()),
+                    Format::JSON => Ok(serde_json::to_string(&t).unwrap()),
+                    f => unsupported(f),
+                }
             })),
+            Output::Classification(format) => ret.push(g.classify().and_then(|t| match format {
+                Format::Plain => Ok(t.to_plaintext()),
+                Format::LaTeX => Ok(t.to_latex()),
+                Format::JSON => Ok(serde_json::to_string(&t).unwrap()),
+                f => unsupported(f),
+            })),
+            Output::LL1Parse(format) => ret.push(match &tokens {
+                Some(tokens) => {
+                    let t = g
+                        .generate_ll1_parsing_table()
+                        .parse_with_recovery(tokens, max_errors);
+                    match format {
+                        Format::Plain => Ok(t.to_plaintext()),
+                        Format::LaTeX => Ok(t.to_latex()),
+                        Format::JSON => Ok(serde_json::to_string(&t).unwrap()),
+                        f => unsupported(f),
+                    }
+                }
+                None => no_input(),
+            }),
+            Output::LRParse(typ, format) => ret.push(match &tokens {
+                Some(tokens) => g.to_lr_fsm(*typ).and_then(|t| {
+                    let t = t.to_parsing_table();
+                    let non_terminals: Vec<&str> = if options.error_non_terminals.is_empty() {
+                        t.non_terminals.iter().map(|s| s.as_str()).collect()
+                    } else {
+                        if let Some(nt) = options
+                            .error_non_terminals
+                            .iter()
+                            .find(|nt| !t.non_terminals.iter().any(|s| s == *nt))
+                        {
+                            return Err(format!("{} is not a non-terminal", nt));
+                        }
+                        options.error_non_terminals.to_vec()
+                    };
+                    let t = t.parse_with_recovery(
+                        tokens,
+                        LRConflictPolicy::Refuse,
+                        &non_terminals,
+                        max_errors,
+                    );
+                    match format {
+                        Format::Plain => Ok(t.to_plaintext()),
+                        Format::LaTeX => Ok(t.to_latex()),
+                        Format::JSON => Ok(serde_json::to_string(&t).unwrap()),
+                        f => unsupported(f),
+                    }
+                }),
+                None => no_input(),
+            }),
+            Output::LL1ParseTree(format) => ret.push(match &tokens {
+                Some(tokens) => g
+                    .generate_ll1_parsing_table()
+                    .parse(tokens)
+                    .to_parse_tree()
+                    .and_then(|t| match format {
+                        Format::Plain => Ok(t.to_plaintext()),
+                        Format::LaTeX => Ok(t.to_latex()),
+                        Format::JSON => Ok(serde_json::to_string(&t).unwrap()),
+                        Format::Dot => Ok(t.to_dot()),
+                        Format::Mermaid => Ok(t.to_mermaid()),
+                        Format::PlantUML => Ok(t.to_plantuml()),
+                        f => unsupported(f),
+                    }),
+                None => no_input(),
+            }),
+            Output::LRParseTree(typ, format) => ret.push(match &tokens {
+                Some(tokens) => g.to_lr_fsm(*typ).and_then(|t| {
+                    t.to_parsing_table()
+                        .parse(tokens, LRConflictPolicy::Refuse)
+                        .to_parse_tree()
+                        .and_then(|t| match format {
+                            Format::Plain => Ok(t.to_plaintext()),
+                            Format::LaTeX => Ok(t.to_latex()),
+                            Format::JSON => Ok(serde_json::to_string(&t).unwrap()),
+                            Format::Dot => Ok(t.to_dot()),
+                            Format::Mermaid => Ok(t.to_mermaid()),
+                            Format::PlantUML => Ok(t.to_plantuml()),
+                            f => unsupported(f),
+                        })
+                }),
+                None => no_input(),
+            }),
+            Output::LeftmostDerivation(format) => ret.push(match &tokens {
+                Some(tokens) => g
+                    .generate_ll1_parsing_table()
+                    .parse(tokens)
+                    .to_leftmost_derivation()
+                    .and_then(|t| match format {
+                        Format::Plain => Ok(t.to_plaintext()),
+                        Format::LaTeX => Ok(t.to_latex()),
+                        Format::JSON => Ok(serde_json::to_string(&t).unwrap()),
+                        f => unsupported(f),
+                    }),
+                None => no_input(),
+            }),
+            Output::RightmostDerivation(typ, format) => ret.push(match &tokens {
+                Some(tokens) => g.to_lr_fsm(*typ).and_then(|t| {
+                    t.to_parsing_table()
+                        .parse(tokens, LRConflictPolicy::Refuse)
+                        .to_rightmost_derivation()
+                        .and_then(|t| match format {
+                            Format::Plain => Ok(t.to_plaintext()),
+                            Format::LaTeX => Ok(t.to_latex()),
+                            Format::JSON => Ok(serde_json::to_string(&t).unwrap()),
+                            f => unsupported(f),
+                        })
+                }),
+                None => no_input(),
+            }),
+            Output::Earley(format) => ret.push(match &tokens {
+                Some(tokens) => {
+                    g.earley_parse(tokens, PARSE_TREE_LIMIT)
+                        .and_then(|t| match format {
+                            Format::Plain => Ok(t.to_plaintext()),
+                            Format::LaTeX => Ok(t.to_latex()),
+                            Format::JSON => Ok(serde_json::to_string(&t).unwrap()),
+                            f => unsupported(f),
+                        })
+                }
+                None => no_input(),
+            }),
+            Output::CYK(format) => ret.push(match &tokens {
+                Some(tokens) => g.cyk(tokens).and_then(|t| match format {
+                    Format::Plain => Ok(t.to_plaintext()),
+                    Format::LaTeX => Ok(t.to_latex()),
+                    Format::JSON => Ok(serde_json::to_string(&t).unwrap()),
+                    f => unsupported(f),
+                }),
+                None => no_input(),
+            }),
+            Output::GLR(typ, format) => ret.push(match &tokens {
+                Some(tokens) => g.to_lr_fsm(*typ).and_then(|t| {
+                    let t = t.to_parsing_table().glr_parse(tokens, PARSE_TREE_LIMIT);
+                    match format {
+                        Format::Plain => Ok(t.to_plaintext()),
+                        Format::LaTeX => Ok(t.to_latex()),
+                        Format::JSON => Ok(serde_json::to_string(&t).unwrap()),
+                        Format::Dot => Ok(t.to_dot()),
+                        f => unsupported(f),
+                    }
+                }),
+                None => no_input(),
+            }),
         }
     }
 
     Ok(ret)
 }
 
+// Wraps LaTeX outputs into a document with the packages they rely on:
+// amsmath for \text and align*, xcolor for conflicts, forest for parse trees
+// and the TikZ automata library for LR automata
+pub fn latex_document(bodies: &[String]) -> String {
+    [
+        "\\documentclass{article}",
+        "\\usepackage[margin=2cm]{geometry}",
+        "\\usepackage{amsmath}",
+        "\\usepackage{xcolor}",
+        "\\usepackage{forest}",
+        "\\usepackage{tikz}",
+        "\\usetikzlibrary{automata, positioning, arrows}",
+        "\\begin{document}",
+        &bodies.join("\n\n"),
+        "\\end{document}",
+    ]
+    .join("\n")
+}
+
+// Every table and automaton of the grammar on one self-contained HTML page
+pub fn grammar_to_html_report(grammar: &str, actions: &[Action]) -> Result<String, String> {
+    let lr_types = [
+        (LRFSMType::LR0, "LR(0)"),
+        (LRFSMType::SLR1, "SLR(1)"),
+        (LRFSMType::LR1, "LR(1)"),
+        (LRFSMType::LALR, "LALR"),
+    ];
+    let mut sections: Vec<(String, Output)> = vec![
+        ("Productions".to_string(), Output::Production(Format::Html)),
+        (
+            "Nullable, first and follow".to_string(),
+            Output::NonTerminal(Format::Html),
+        ),
+        (
+            "LL(1) parsing table".to_string(),
+            Output::LL1ParsingTable(Format::Html),
+        ),
+    ];
+    for (t, name) in lr_types {
+        sections.push((
+            format!("{} automaton", name),
+            Output::LRFSM(t, Format::Html),
+        ));
+        sections.push((
+            format!("{} parsing table", name),
+            Output::LRParsingTable(t, Format::Html),
+        ));
+    }
+
+    let outputs: Vec<Output> = sections.iter().map(|(_, o)| *o).collect();
+    let results = grammar_to_output(grammar, actions, &outputs)?;
+
+    let mut body: Vec<String> = Vec::new();
+    for ((title, _), result) in sections.iter().zip(results) {
+        body.push(format!("<h2>{}</h2>", title));
+        body.push(match result {
+            Ok(html) => html,
+            Err(e) => format!(
+                "<p class=\"error\">{}</p>",
+                crowbook_text_processing::escape::html(e)
+            ),
+        });
+    }
+
+    Ok([
+        "<!DOCTYPE html>",
+        "<html>",
+        "<head>",
+        "<meta charset=\"utf-8\">",
+        "<title>Grammar report</title>",
+        "<style>",
+        "body { font-family: sans-serif; }",
+        "table { border-collapse: collapse; margin-bottom: 1em; }",
+        "th, td { border: 1px solid #999; padding: 2px 6px; font-family: monospace; white-space: nowrap; }",
+        ".conflict, .error { color: red; }",
+        "</style>",
+        "</head>",
+        "<body>",
+        "<h1>Grammar report</h1>",
+        &body.join("\n"),
+        "</body>",
+        "</html>",
+    ]
+    .join("\n"))
+}
+
+fn unsupported(format: &Format) -> Result<String, String> {
+    Err(format!(
+        "{:?} format is not supported by this output",
+        format
+    ))
+}
+
 #[cfg(test)]
 mod parse_tests {
     use crate::grammar::EPSILON;
@@ -141,14 +543,14 @@ mod parse_tests {
     fn simple_parse() {
         let g = crate::Grammar::parse("S -> a").unwrap();
 
-        let s = g.symbol_table.get("S").unwrap().clone();
-        let a = g.symbol_table.get("a").unwrap().clone();
-        let epsilon = g.symbol_table.get(EPSILON).unwrap().clone();
+        let s = *g.symbol_table.get("S").unwrap();
+        let a = *g.symbol_table.get("a").unwrap();
+        let epsilon = *g.symbol_table.get(EPSILON).unwrap();
 
         assert_eq!(g.get_symbol_name(s), "S");
         assert_eq!(g.get_symbol_name(a), "a");
 
-        assert_eq!(g.symbols[epsilon].non_terminal().unwrap().nullable, true);
+        assert!(g.symbols[epsilon].non_terminal().unwrap().nullable);
 
         assert_eq!(g.symbols[s].non_terminal().unwrap().productions[0], vec![a]);
     }
@@ -157,8 +559,8 @@ mod parse_tests {
     fn simple_parse_with_space() {
         let g = crate::Grammar::parse("  S -> a ").unwrap();
 
-        let s = g.symbol_table.get("S").unwrap().clone();
-        let a = g.symbol_table.get("a").unwrap().clone();
+        let s = *g.symbol_table.get("S").unwrap();
+        let a = *g.symbol_table.get("a").unwrap();
 
         assert_eq!(g.get_symbol_name(s), "S");
         assert_eq!(g.get_symbol_name(a), "a");
@@ -170,10 +572,10 @@ mod parse_tests {
     fn simple_parse_with_space_and_newline() {
         let g = crate::Grammar::parse("  S -> a \n | b c").unwrap();
 
-        let s = g.symbol_table.get("S").unwrap().clone();
-        let a = g.symbol_table.get("a").unwrap().clone();
-        let b = g.symbol_table.get("b").unwrap().clone();
-        let c = g.symbol_table.get("c").unwrap().clone();
+        let s = *g.symbol_table.get("S").unwrap();
+        let a = *g.symbol_table.get("a").unwrap();
+        let b = *g.symbol_table.get("b").unwrap();
+        let c = *g.symbol_table.get("c").unwrap();
 
         assert_eq!(g.get_symbol_name(s), "S");
         assert_eq!(g.get_symbol_name(a), "a");
@@ -238,4 +640,1338 @@ mod generate_ll1_parsing_table_test {
         let result = g.generate_ll1_parsing_table();
         println!("{}", result.to_plaintext());
     }
+
+    #[test]
+    fn indirectly_nullable_test() {
+        let mut g = crate::Grammar::parse("S -> A a\nA -> B b | B\nB -> ϵ").unwrap();
+        // A -> B is predicted on FOLLOW(A), not a synthetic A -> ϵ
+        assert_eq!(
+            g.generate_ll1_parsing_table().to_plaintext(),
+            "  | $ |        a |        b\n\
+             S |   | S -> A a | S -> A a\n\
+             A |   |   A -> B | A -> B b\n\
+             B |   |   B -> ϵ |   B -> ϵ"
+        );
+    }
+}
+
+#[cfg(test)]
+mod left_factoring_test {
+    #[test]
+    fn dangling_else_test() {
+        let mut g =
+            crate::Grammar::parse("S -> if E then S | if E then S else S | a\nE -> b").unwrap();
+        g.left_factor();
+
+        let output = g.to_production_output_vec().to_plaintext();
+        assert_eq!(
+            output,
+            " S -> if E then S S'\n    | a\n E -> b\nS' -> ϵ\n    | else S"
+        );
+    }
+
+    #[test]
+    fn nested_prefix_test() {
+        let mut g = crate::Grammar::parse("A -> a b c | a b d | a e").unwrap();
+        g.left_factor();
+
+        let a = g.get_symbol_by_name("A").non_terminal().unwrap();
+        assert_eq!(a.productions.len(), 1);
+        let a_prime = g.get_symbol_by_name("A'").non_terminal().unwrap();
+        assert_eq!(a_prime.productions.len(), 2);
+        let a_prime_prime = g.get_symbol_by_name("A''").non_terminal().unwrap();
+        assert_eq!(
+            g.production_to_vec_str(&a_prime_prime.productions[0]),
+            vec!["c"]
+        );
+        assert_eq!(
+            g.production_to_vec_str(&a_prime_prime.productions[1]),
+            vec!["d"]
+        );
+    }
+}
+
+#[cfg(test)]
+mod eliminate_epsilon_productions_test {
+    #[test]
+    fn nullable_start_test() {
+        let mut g = crate::Grammar::parse("S -> a S b | S S | ϵ").unwrap();
+        g.eliminate_epsilon_productions();
+
+        let output = g.to_production_output_vec().to_plaintext();
+        assert_eq!(
+            output,
+            " S -> a S b\n    | a b\n    | S S\nS' -> S\n    | ϵ"
+        );
+        assert_eq!(g.get_symbol_name(g.start_symbol.unwrap()), "S'");
+    }
+
+    #[test]
+    fn epsilon_only_non_terminal_test() {
+        let mut g = crate::Grammar::parse("S -> A B c\nA -> a | ϵ\nB -> ϵ").unwrap();
+        g.eliminate_epsilon_productions();
+
+        let output = g.to_production_output_vec().to_plaintext();
+        assert_eq!(output, "S -> A c\n   | c\nA -> a");
+        assert!(g.get_symbol_index("B").is_none());
+    }
+
+    #[test]
+    fn many_nullable_symbols_test() {
+        let grammar = format!("S -> {}c\nB -> b | ϵ", "B ".repeat(70));
+        let mut g = crate::Grammar::parse(&grammar).unwrap();
+        g.eliminate_epsilon_productions();
+
+        let s = g.get_symbol_by_name("S").non_terminal().unwrap();
+        assert_eq!(s.productions.len(), 71);
+        assert!(s
+            .productions
+            .iter()
+            .any(|p| g.production_to_vec_str(p) == ["c"]));
+    }
+}
+
+#[cfg(test)]
+mod eliminate_unit_productions_test {
+    #[test]
+    fn cycle_test() {
+        let mut g = crate::Grammar::parse("E -> T | E + T\nT -> F | T * F\nF -> E | id").unwrap();
+        let report = g.eliminate_unit_productions();
+
+        assert_eq!(report.cycles, vec![vec!["E", "T", "F"]]);
+        assert_eq!(report.inlined.len(), 6);
+
+        for nt in g.non_terminal_iter() {
+            let mut productions: Vec<Vec<&str>> = nt
+                .productions
+                .iter()
+                .map(|p| g.production_to_vec_str(p))
+                .collect();
+            productions.sort();
+            assert_eq!(
+                productions,
+                vec![vec!["E", "+", "T"], vec!["T", "*", "F"], vec!["id"]]
+            );
+        }
+    }
+
+    #[test]
+    fn output_test() {
+        use crate::{grammar_to_output, Format, Output};
+
+        let output = grammar_to_output(
+            "S -> A | a\nA -> b",
+            &[],
+            &[
+                Output::UnitProductions(Format::Plain),
+                Output::UnitProductions(Format::LaTeX),
+                Output::UnitProductions(Format::JSON),
+                Output::Production(Format::Plain),
+            ],
+        )
+        .unwrap();
+        assert_eq!(output[0], Ok("S =>* A".to_string()));
+        assert!(output[1]
+            .as_ref()
+            .unwrap()
+            .contains("S $\\Rightarrow^*$ A\\\\"));
+        assert_eq!(
+            output[2],
+            Ok("{\"inlined\":[[\"S\",\"A\"]],\"cycles\":[]}".to_string())
+        );
+        // only reported, the unit production is still there
+        assert_eq!(output[3], Ok("S -> A\n   | a\nA -> b".to_string()));
+    }
+}
+
+#[cfg(test)]
+mod useless_symbols_test {
+    #[test]
+    fn remove_test() {
+        let mut g =
+            crate::Grammar::parse("S -> a S | A | C\nA -> a\nB -> b b\nC -> a C b").unwrap();
+        let report = g.remove_useless_symbols();
+
+        assert_eq!(report.non_productive, vec!["C"]);
+        assert_eq!(report.unreachable, vec!["B", "b"]);
+        assert_eq!(
+            g.to_production_output_vec().to_plaintext(),
+            "S -> a S\n   | A\nA -> a"
+        );
+        assert!(g.get_symbol_index("b").is_none());
+        assert_eq!(g.get_symbol_name(g.start_symbol.unwrap()), "S");
+    }
+
+    #[test]
+    fn empty_language_test() {
+        let mut g = crate::Grammar::parse("S -> a S").unwrap();
+        let report = g.remove_useless_symbols();
+
+        assert_eq!(report.non_productive, vec!["S"]);
+        assert_eq!(report.unreachable, vec!["a"]);
+        assert!(g.start_symbol.is_some());
+    }
+
+    #[test]
+    fn cache_test() {
+        let mut g = crate::Grammar::parse("S -> S | S S").unwrap();
+        g.calculate_nullable_first_follow();
+        let report = g.remove_useless_symbols();
+
+        assert_eq!(report.non_productive, vec!["S"]);
+        assert!(report.unreachable.is_empty());
+        assert!(g.is_nullable_first_follow_valid());
+        let start_idx = g.start_symbol.unwrap();
+        let nt = g.symbols[start_idx].non_terminal().unwrap();
+        assert!(nt.productions.is_empty());
+        assert!(!nt.nullable && nt.first.is_empty());
+        assert_eq!(nt.follow.len(), 1);
+    }
+}
+
+#[cfg(test)]
+mod chomsky_normal_form_test {
+    use crate::grammar::{grammar::Symbol, EPSILON};
+
+    #[test]
+    fn normal_form_test() {
+        let mut g = crate::Grammar::parse("S -> A S A | a B\nA -> B | S\nB -> b | ϵ").unwrap();
+        g.to_chomsky_normal_form();
+
+        let start = g.start_symbol.unwrap();
+        assert_eq!(g.get_symbol_name(start), "S'");
+        for nt in g.non_terminal_iter() {
+            for production in &nt.productions {
+                let symbols = g.production_to_vec_str(production);
+                match production.len() {
+                    1 if symbols[0] == EPSILON => assert_eq!(nt.index, start),
+                    1 => assert!(matches!(g.symbols[production[0]], Symbol::Terminal(_))),
+                    2 => assert!(production.iter().all(|&s| {
+                        matches!(g.symbols[s], Symbol::NonTerminal(_)) && s != start
+                    })),
+                    _ => panic!("{} -> {}", nt.name, symbols.join(" ")),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn binarization_test() {
+        let mut g = crate::Grammar::parse("S -> a S b | a b").unwrap();
+        g.to_chomsky_normal_form();
+
+        assert_eq!(
+            g.to_production_output_vec().to_plaintext(),
+            "  S -> T_a S''\n     | T_a T_b\n S' -> T_a S''\n     | T_a T_b\nT_a -> a\nT_b -> b\nS'' -> S T_b"
+        );
+    }
+
+    #[test]
+    fn nullable_start_after_action_test() {
+        let mut g = crate::Grammar::parse("S -> ϵ | a S").unwrap();
+        g.left_factor();
+        g.to_chomsky_normal_form();
+
+        let start = g.start_symbol.unwrap();
+        let epsilon = g.get_symbol_index(EPSILON).unwrap();
+        assert!(g.symbols[start]
+            .non_terminal()
+            .unwrap()
+            .productions
+            .contains(&vec![epsilon]));
+    }
+}
+
+#[cfg(test)]
+mod greibach_normal_form_test {
+    use crate::grammar::{grammar::Symbol, EPSILON};
+
+    fn assert_greibach_normal_form(g: &crate::Grammar) {
+        let start = g.start_symbol.unwrap();
+        for nt in g.non_terminal_iter() {
+            for production in &nt.productions {
+                let symbols = g.production_to_vec_str(production);
+                if symbols == vec![EPSILON] {
+                    assert_eq!(nt.index, start);
+                    continue;
+                }
+                assert!(matches!(g.symbols[production[0]], Symbol::Terminal(_)));
+                assert!(production[1..]
+                    .iter()
+                    .all(|&s| matches!(g.symbols[s], Symbol::NonTerminal(_)) && s != start));
+            }
+        }
+    }
+
+    #[test]
+    fn indirect_left_recursion_test() {
+        let mut g = crate::Grammar::parse("S -> A B\nA -> B S | b\nB -> S A | a").unwrap();
+        g.to_greibach_normal_form().unwrap();
+        assert_greibach_normal_form(&g);
+    }
+
+    #[test]
+    fn expression_test() {
+        let mut g =
+            crate::Grammar::parse("E -> E + T | T\nT -> T * F | F\nF -> ( E ) | id | ϵ").unwrap();
+        g.to_greibach_normal_form().unwrap();
+        assert_greibach_normal_form(&g);
+    }
+}
+
+#[cfg(test)]
+mod eliminate_left_recursion_test {
+    use crate::grammar::eliminate_left_recursion::LeftRecursionError;
+
+    #[test]
+    fn hidden_left_recursion_test() {
+        let mut g = crate::Grammar::parse("A -> B A a | b\nB -> ϵ | c").unwrap();
+        assert_eq!(
+            g.eliminate_left_recursion(),
+            Err(LeftRecursionError::HiddenLeftRecursion {
+                derivation: vec!["A".to_string(), "B A a".to_string(), "A a".to_string()]
+            })
+        );
+    }
+
+    #[test]
+    fn cycle_test() {
+        let mut g = crate::Grammar::parse("E -> T | a\nT -> F\nF -> E").unwrap();
+        assert!(matches!(
+            g.eliminate_left_recursion(),
+            Err(LeftRecursionError::Cycle { .. })
+        ));
+    }
+
+    #[test]
+    fn indirect_left_recursion_test() {
+        let mut g = crate::Grammar::parse("A -> B a\nB -> C b | x\nC -> A c | y").unwrap();
+        g.eliminate_left_recursion().unwrap();
+        assert_eq!(
+            g.to_production_output_vec().to_plaintext(),
+            " A -> B a\n B -> C b\n    | x\n C -> x a c C'\n    | y C'\nC' -> b a c C'\n    | ϵ"
+        );
+    }
+
+    #[test]
+    fn nullable_prefix_substitution_test() {
+        let mut g = crate::Grammar::parse("A -> B c | d\nB -> ϵ | A b").unwrap();
+        g.eliminate_left_recursion().unwrap();
+        assert_eq!(
+            g.to_production_output_vec().to_plaintext(),
+            " A -> B c\n    | d\n B -> B'\n    | d b B'\nB' -> c b B'\n    | ϵ"
+        );
+    }
+
+    #[test]
+    fn expression_grammar_test() {
+        let mut g =
+            crate::Grammar::parse("E -> E + T | T\nT -> T * F | F\nF -> ( E ) | id").unwrap();
+        g.eliminate_left_recursion().unwrap();
+        assert_eq!(
+            g.to_production_output_vec().to_plaintext(),
+            " E -> T E'\n T -> F T'\n F -> ( E )\n    | id\nE' -> + T E'\n    | ϵ\nT' -> * F T'\n    | ϵ"
+        );
+    }
+
+    #[test]
+    fn re_expansion_test() {
+        let mut g = crate::Grammar::parse("S -> A a | b\nA -> B c\nB -> S d | e").unwrap();
+        g.eliminate_left_recursion().unwrap();
+        assert_eq!(
+            g.to_production_output_vec().to_plaintext(),
+            " S -> A a\n    | b\n A -> B c\n B -> b d B'\n    | e B'\nB' -> c a d B'\n    | ϵ"
+        );
+    }
+}
+
+#[cfg(test)]
+mod lr_parsing_table_test {
+    use crate::LRFSMType;
+
+    fn conflict_count(grammar: &str, t: LRFSMType) -> usize {
+        let mut g = crate::Grammar::parse(grammar).unwrap();
+        let table = g.to_lr_fsm(t).unwrap().to_parsing_table();
+        table
+            .action
+            .iter()
+            .flatten()
+            .filter(|actions| actions.len() > 1)
+            .count()
+    }
+
+    #[test]
+    fn lr0_slr1_test() {
+        let grammar = "E -> E + T | T\nT -> T * F | F\nF -> ( E ) | id";
+        assert_eq!(conflict_count(grammar, LRFSMType::LR0), 2);
+        assert_eq!(conflict_count(grammar, LRFSMType::SLR1), 0);
+    }
+
+    #[test]
+    fn lr1_nullable_lookahead_test() {
+        let mut g = crate::Grammar::parse("S -> A B\nA -> a\nB -> b | ϵ").unwrap();
+        let table = g.to_lr_fsm(LRFSMType::LR1).unwrap().to_parsing_table();
+        let reduce_a = table
+            .action
+            .iter()
+            .flatten()
+            .filter(|actions| actions.iter().any(|a| a.to_plaintext() == "r(A -> a)"))
+            .count();
+        assert_eq!(reduce_a, 2);
+    }
+
+    #[test]
+    fn lr1_lookahead_propagation_test() {
+        let grammar = "E -> T | E + T\nT -> id";
+        assert_eq!(conflict_count(grammar, LRFSMType::LR1), 0);
+        let mut g = crate::Grammar::parse(grammar).unwrap();
+        let table = g.to_lr_fsm(LRFSMType::LR1).unwrap().to_parsing_table();
+        let plus = table.terminals.iter().position(|t| t == "+").unwrap();
+        assert!(table
+            .action
+            .iter()
+            .any(|row| row[plus].iter().any(|a| a.to_plaintext() == "r(T -> id)")));
+    }
+}
+
+#[cfg(test)]
+mod conflict_test {
+    use crate::grammar::conflict::{ConflictKind, ConflictLocation};
+    use crate::LRFSMType;
+
+    const DANGLING_ELSE: &str = "S -> if E then S S2 | a\nS2 -> else S | ϵ\nE -> b";
+
+    #[test]
+    fn ll1_first_follow_test() {
+        let mut g = crate::Grammar::parse(DANGLING_ELSE).unwrap();
+        let report = g.generate_ll1_parsing_table().conflicts();
+        assert_eq!(report.conflicts.len(), 1);
+        let conflict = &report.conflicts[0];
+        assert_eq!(
+            conflict.location,
+            ConflictLocation::NonTerminal("S2".to_string())
+        );
+        assert_eq!(conflict.lookahead, "else");
+        assert_eq!(conflict.kind, ConflictKind::FirstFollow);
+    }
+
+    #[test]
+    fn lr_shift_reduce_test() {
+        let mut g = crate::Grammar::parse(DANGLING_ELSE).unwrap();
+        let report = g
+            .to_lr_fsm(LRFSMType::LALR)
+            .unwrap()
+            .to_parsing_table()
+            .conflicts();
+        assert_eq!(report.conflicts.len(), 1);
+        let conflict = &report.conflicts[0];
+        assert_eq!(conflict.kind, ConflictKind::ShiftReduce);
+        assert!(conflict.shift.is_some());
+        assert_eq!(
+            conflict.productions,
+            vec![("S2".to_string(), vec!["ϵ".to_string()])]
+        );
+    }
+
+    #[test]
+    fn reduce_reduce_test() {
+        let mut g = crate::Grammar::parse("S -> A a | B a\nA -> c\nB -> c").unwrap();
+        let report = g
+            .to_lr_fsm(LRFSMType::SLR1)
+            .unwrap()
+            .to_parsing_table()
+            .conflicts();
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].kind, ConflictKind::ReduceReduce);
+    }
+
+    #[test]
+    fn accept_reduce_test() {
+        let mut g = crate::Grammar::parse("S -> S A | a\nA -> ϵ").unwrap();
+        let report = g
+            .to_lr_fsm(LRFSMType::SLR1)
+            .unwrap()
+            .to_parsing_table()
+            .conflicts();
+        let conflict = report.conflicts.iter().find(|c| c.accept).unwrap();
+        assert_eq!(conflict.lookahead, "$");
+        assert_eq!(conflict.kind, ConflictKind::AcceptReduce);
+        assert_eq!(
+            conflict.productions,
+            vec![("A".to_string(), vec!["ϵ".to_string()])]
+        );
+    }
+}
+
+#[cfg(test)]
+mod classify_test {
+    #[test]
+    fn lalr_not_slr_test() {
+        let mut g = crate::Grammar::parse("S -> L = R | R\nL -> * R | id\nR -> L").unwrap();
+        let c = g.classify().unwrap();
+        assert!(!c.is("LL(1)"));
+        assert!(!c.is("LR(0)"));
+        assert!(!c.is("SLR(1)"));
+        assert!(c.is("LALR(1)"));
+        assert!(c.is("LR(1)"));
+    }
+
+    #[test]
+    fn lr1_not_lalr_test() {
+        let mut g =
+            crate::Grammar::parse("S -> a A d | b B d | a B e | b A e\nA -> c\nB -> c").unwrap();
+        let c = g.classify().unwrap();
+        assert!(!c.is("LALR(1)"));
+        assert!(c.is("LR(1)"));
+    }
+
+    #[test]
+    fn ll1_test() {
+        let mut g = crate::Grammar::parse("E -> T E'\nE' -> + T E' | ϵ\nT -> id").unwrap();
+        assert!(g.classify().unwrap().is("LL(1)"));
+    }
+}
+
+#[cfg(test)]
+mod ll1_parse_test {
+    use crate::grammar::ll1_parse::LL1ParseAction;
+
+    const EXPR: &str = "E -> T E'\nE' -> + T E' | ϵ\nT -> F T'\nT' -> * F T' | ϵ\nF -> ( E ) | id";
+
+    #[test]
+    fn accept_test() {
+        let mut g = crate::Grammar::parse(EXPR).unwrap();
+        let trace = g
+            .generate_ll1_parsing_table()
+            .parse(&["id", "+", "id", "*", "id"]);
+        assert!(trace.accepted);
+        assert_eq!(trace.steps.len(), 17);
+        assert_eq!(trace.steps[0].stack, vec!["$", "E"]);
+        assert_eq!(
+            trace.steps[0].action,
+            LL1ParseAction::Predict("E".to_string(), vec!["T".to_string(), "E'".to_string()])
+        );
+        assert_eq!(
+            trace.steps[3].action,
+            LL1ParseAction::Match("id".to_string())
+        );
+        assert_eq!(trace.steps[16].action, LL1ParseAction::Accept);
+    }
+
+    #[test]
+    fn error_test() {
+        let mut g = crate::Grammar::parse(EXPR).unwrap();
+        let trace = g.generate_ll1_parsing_table().parse(&["id", "+", ")"]);
+        assert!(!trace.accepted);
+        let last = trace.steps.last().unwrap();
+        assert_eq!(last.input, vec![")", "$"]);
+        assert!(matches!(last.action, LL1ParseAction::Error(_)));
+    }
+
+    #[test]
+    fn plaintext_test() {
+        let mut g = crate::Grammar::parse("S -> a S | ϵ").unwrap();
+        let trace = g.generate_ll1_parsing_table().parse(&["a"]);
+        assert_eq!(
+            trace.to_plaintext(),
+            "Stack | Input | Action\n\
+             $ S   |   a $ | S -> a S\n\
+             $ S a |   a $ | match a\n\
+             $ S   |     $ | S -> ϵ\n\
+             $     |     $ | accept"
+        );
+    }
+}
+
+#[cfg(test)]
+mod lr_parse_test {
+    use crate::grammar::lr_parse::LRParseAction;
+    use crate::{LRConflictPolicy, LRFSMType};
+
+    #[test]
+    fn accept_test() {
+        let mut g =
+            crate::Grammar::parse("E -> E + T | T\nT -> T * F | F\nF -> ( E ) | id").unwrap();
+        let trace = g
+            .to_lr_fsm(LRFSMType::SLR1)
+            .unwrap()
+            .to_parsing_table()
+            .parse(&["id", "*", "id", "+", "id"], LRConflictPolicy::Refuse);
+        assert!(trace.accepted);
+        assert_eq!(trace.steps.len(), 14);
+        assert_eq!(trace.steps[0].action, LRParseAction::Shift(5));
+        assert_eq!(
+            trace.steps[1].action,
+            LRParseAction::Reduce("F".to_string(), vec!["id".to_string()])
+        );
+        assert_eq!(trace.steps[13].symbols, vec!["$", "E"]);
+    }
+
+    #[test]
+    fn epsilon_test() {
+        let mut g = crate::Grammar::parse("S -> a S b | ϵ").unwrap();
+        let trace = g
+            .to_lr_fsm(LRFSMType::LALR)
+            .unwrap()
+            .to_parsing_table()
+            .parse(&["a", "b"], LRConflictPolicy::Refuse);
+        assert!(trace.accepted);
+        assert_eq!(
+            trace.steps[1].action,
+            LRParseAction::Reduce("S".to_string(), vec!["ϵ".to_string()])
+        );
+        assert_eq!(trace.steps[2].symbols, vec!["$", "a", "S"]);
+    }
+
+    #[test]
+    fn error_test() {
+        let mut g = crate::Grammar::parse("S -> a S b | ϵ").unwrap();
+        let trace = g
+            .to_lr_fsm(LRFSMType::LALR)
+            .unwrap()
+            .to_parsing_table()
+            .parse(&["a", "b", "b"], LRConflictPolicy::Refuse);
+        assert!(!trace.accepted);
+        let last = trace.steps.last().unwrap();
+        assert_eq!(last.input, vec!["b", "$"]);
+        assert!(matches!(last.action, LRParseAction::Error(_)));
+    }
+
+    #[test]
+    fn conflict_policy_test() {
+        let mut g = crate::Grammar::parse("S -> if S S2 | other\nS2 -> else S | ϵ").unwrap();
+        let table = g.to_lr_fsm(LRFSMType::LALR).unwrap().to_parsing_table();
+        let tokens = ["if", "if", "other", "else", "other"];
+        assert!(!table.parse(&tokens, LRConflictPolicy::Refuse).accepted);
+        assert!(table.parse(&tokens, LRConflictPolicy::PreferShift).accepted);
+    }
+}
+
+#[cfg(test)]
+mod parse_tree_test {
+    use crate::{LRConflictPolicy, LRFSMType};
+
+    const EXPR: &str = "E -> T E'\nE' -> + T E' | ϵ\nT -> F T'\nT' -> * F T' | ϵ\nF -> ( E ) | id";
+
+    #[test]
+    fn ll1_lr_same_tree_test() {
+        let tokens = ["(", "id", "+", "id", ")", "*", "id"];
+        let mut g = crate::Grammar::parse(EXPR).unwrap();
+        let ll1 = g
+            .generate_ll1_parsing_table()
+            .parse(&tokens)
+            .to_parse_tree()
+            .unwrap();
+        let lr = g
+            .to_lr_fsm(LRFSMType::LALR)
+            .unwrap()
+            .to_parsing_table()
+            .parse(&tokens, LRConflictPolicy::Refuse)
+            .to_parse_tree()
+            .unwrap();
+        assert_eq!(ll1, lr);
+        assert_eq!(ll1.leaves(), tokens.to_vec());
+    }
+
+    #[test]
+    fn plaintext_test() {
+        let mut g = crate::Grammar::parse("S -> a S | ϵ").unwrap();
+        let t = g
+            .generate_ll1_parsing_table()
+            .parse(&["a"])
+            .to_parse_tree()
+            .unwrap();
+        assert_eq!(t.to_plaintext(), "S\n├── a\n└── S\n    └── ϵ");
+        assert_eq!(
+            t.to_latex(),
+            "\\begin{forest}\n[{$S$}\n  [{$\\text{a}$}]\n  [{$S$}\n    [{$\\epsilon$}]]]\n\\end{forest}"
+        );
+    }
+
+    #[test]
+    fn not_accepted_test() {
+        let mut g = crate::Grammar::parse("S -> a S | ϵ").unwrap();
+        assert!(g
+            .generate_ll1_parsing_table()
+            .parse(&["b"])
+            .to_parse_tree()
+            .is_err());
+    }
+
+    #[test]
+    fn indirect_nullable_test() {
+        let mut g = crate::Grammar::parse("S -> A a\nA -> B b | B\nB -> ϵ").unwrap();
+        let ll1 = g
+            .generate_ll1_parsing_table()
+            .parse(&["a"])
+            .to_parse_tree()
+            .unwrap();
+        let lr = g
+            .to_lr_fsm(LRFSMType::SLR1)
+            .unwrap()
+            .to_parsing_table()
+            .parse(&["a"], LRConflictPolicy::Refuse)
+            .to_parse_tree()
+            .unwrap();
+        assert_eq!(ll1, lr);
+        assert_eq!(
+            ll1.to_plaintext(),
+            "S\n├── A\n│   └── B\n│       └── ϵ\n└── a"
+        );
+    }
+}
+
+#[cfg(test)]
+mod derivation_test {
+    use crate::{LRConflictPolicy, LRFSMType};
+
+    #[test]
+    fn leftmost_test() {
+        let mut g = crate::Grammar::parse("S -> a S | ϵ").unwrap();
+        let d = g
+            .generate_ll1_parsing_table()
+            .parse(&["a", "a"])
+            .to_leftmost_derivation()
+            .unwrap();
+        assert_eq!(d.to_plaintext(), "     S\n=>lm a S\n=>lm a a S\n=>lm a a");
+    }
+
+    #[test]
+    fn leftmost_real_productions_test() {
+        let mut g = crate::Grammar::parse("S -> A a\nA -> B b | B\nB -> ϵ").unwrap();
+        let d = g
+            .generate_ll1_parsing_table()
+            .parse(&["a"])
+            .to_leftmost_derivation()
+            .unwrap();
+        assert_eq!(d.to_plaintext(), "     S\n=>lm A a\n=>lm B a\n=>lm a");
+
+        // each step rewrites the leftmost non-terminal by one of its productions
+        for w in d.forms.windows(2) {
+            let (before, after) = (&w[0].symbols, &w[1].symbols);
+            let i = before
+                .iter()
+                .position(|s| {
+                    g.get_symbol_index(s)
+                        .is_some_and(|idx| g.symbols[idx].non_terminal().is_some())
+                })
+                .unwrap();
+            let nt = g.get_symbol_by_name(&before[i]).non_terminal().unwrap();
+            assert!(nt.productions.iter().any(|p| {
+                let right: Vec<&str> = g
+                    .production_to_vec_str(p)
+                    .into_iter()
+                    .filter(|s| *s != crate::grammar::EPSILON)
+                    .collect();
+                before[..i]
+                    .iter()
+                    .map(|s| s.as_str())
+                    .chain(right)
+                    .chain(before[i + 1..].iter().map(|s| s.as_str()))
+                    .eq(after.iter().map(|s| s.as_str()))
+            }));
+        }
+    }
+
+    #[test]
+    fn rightmost_test() {
+        let mut g =
+            crate::Grammar::parse("E -> E + T | T\nT -> T * F | F\nF -> ( E ) | id").unwrap();
+        let d = g
+            .to_lr_fsm(LRFSMType::SLR1)
+            .unwrap()
+            .to_parsing_table()
+            .parse(&["id", "+", "id"], LRConflictPolicy::Refuse)
+            .to_rightmost_derivation()
+            .unwrap();
+        assert_eq!(
+            d.to_plaintext(),
+            "     E\n\
+             =>rm [E + T]\n\
+             =>rm E + [F]\n\
+             =>rm E + [id]\n\
+             =>rm [T] + id\n\
+             =>rm [F] + id\n\
+             =>rm [id] + id"
+        );
+        assert!(d
+            .to_latex()
+            .contains("&\\Rightarrow_{rm} E \\  \\text{+} \\  \\underline{F}\\\\"));
+    }
+
+    #[test]
+    fn epsilon_handle_test() {
+        let mut g = crate::Grammar::parse("S -> a S b | ϵ").unwrap();
+        let d = g
+            .to_lr_fsm(LRFSMType::LALR)
+            .unwrap()
+            .to_parsing_table()
+            .parse(&["a", "b"], LRConflictPolicy::Refuse)
+            .to_rightmost_derivation()
+            .unwrap();
+        assert_eq!(d.to_plaintext(), "     S\n=>rm [a S b]\n=>rm a [ϵ] b");
+    }
+}
+
+#[cfg(test)]
+mod earley_test {
+    #[test]
+    fn ambiguous_test() {
+        let mut g = crate::Grammar::parse("E -> E + E | E * E | id").unwrap();
+        let tokens = ["id", "+", "id", "*", "id"];
+        let r = g.earley_parse(&tokens, 10).unwrap();
+        assert!(r.accepted);
+        assert_eq!(r.chart.len(), 6);
+        assert_eq!(r.trees.len(), 2);
+        assert_ne!(r.trees[0], r.trees[1]);
+        for t in &r.trees {
+            assert_eq!(t.leaves(), tokens.to_vec());
+        }
+
+        let r = g.earley_parse(&tokens, 1).unwrap();
+        assert_eq!(r.trees.len(), 1);
+    }
+
+    #[test]
+    fn nullable_test() {
+        let mut g = crate::Grammar::parse("S -> A S b | ϵ\nA -> a | ϵ").unwrap();
+        let r = g.earley_parse(&["a", "b", "b"], 10).unwrap();
+        assert!(r.accepted);
+        assert_eq!(r.trees.len(), 2);
+        assert!(g.earley_parse(&[], 10).unwrap().accepted);
+        assert!(!g.earley_parse(&["a", "a", "b"], 10).unwrap().accepted);
+    }
+
+    #[test]
+    fn cycle_test() {
+        let mut g = crate::Grammar::parse("S -> S | a").unwrap();
+        let r = g.earley_parse(&["a"], 10).unwrap();
+        assert!(r.accepted);
+        assert_eq!(r.trees.len(), 1);
+        assert_eq!(r.trees[0].to_plaintext(), "S\n└── a");
+    }
+
+    #[test]
+    fn long_ambiguous_test() {
+        let mut g = crate::Grammar::parse("E -> E + E | E * E | id").unwrap();
+        // Catalan(20) trees, only the first 10 may be built
+        let tokens: Vec<&str> = std::iter::once("id")
+            .chain(["+", "id"].repeat(20))
+            .collect();
+        let now = std::time::Instant::now();
+        let r = g.earley_parse(&tokens, 10).unwrap();
+        assert!(now.elapsed() < std::time::Duration::from_secs(5));
+        assert_eq!(r.trees.len(), 10);
+        for t in &r.trees {
+            assert_eq!(t.leaves(), tokens);
+        }
+    }
+}
+
+#[cfg(test)]
+mod cyk_test {
+    const G: &str = "S -> A B | B C\nA -> B A | a\nB -> C C | b\nC -> A B | a";
+
+    #[test]
+    fn table_test() {
+        let g = crate::Grammar::parse(G).unwrap();
+        let t = g.cyk(&["b", "a", "a", "b", "a"]).unwrap();
+        assert!(t.accepted);
+        assert_eq!(t.table.len(), 5);
+        assert_eq!(t.table[0][0], vec!["B"]);
+        assert_eq!(t.table[0][1], vec!["A", "C"]);
+        assert!(t.table[2][0].is_empty());
+        assert_eq!(
+            t.to_plaintext(),
+            "{A, C, S'}\n\
+             ∅          | {A, C, S'}\n\
+             ∅          | {B}        | {B}\n\
+             {A, S'}    | {B}        | {C, S'} | {A, S'}\n\
+             {B}        | {A, C}     | {A, C}  | {B}     | {A, C}\n\
+             b          | a          | a       | b       | a\n\
+             accepted (start symbol S')"
+        );
+        // the grammar itself is left untouched
+        assert_eq!(g.start_symbol, g.get_symbol_index("S"));
+    }
+
+    #[test]
+    fn reject_test() {
+        let g = crate::Grammar::parse(G).unwrap();
+        assert!(!g.cyk(&["a", "b", "b"]).unwrap().accepted);
+        assert!(!g.cyk(&["c"]).unwrap().accepted);
+    }
+
+    #[test]
+    fn empty_input_after_action_test() {
+        let mut g = crate::Grammar::parse("S -> ϵ | a S").unwrap();
+        g.left_factor();
+        assert!(g.cyk(&[]).unwrap().accepted);
+        g.to_chomsky_normal_form();
+        assert!(g.cyk(&[]).unwrap().accepted);
+        assert!(g.cyk(&["a", "a"]).unwrap().accepted);
+    }
+
+    #[test]
+    fn epsilon_test() {
+        let g = crate::Grammar::parse("S -> a S b | ϵ").unwrap();
+        assert!(g.cyk(&[]).unwrap().accepted);
+        assert!(g.cyk(&["a", "a", "b", "b"]).unwrap().accepted);
+        assert!(!g.cyk(&["a", "b", "b"]).unwrap().accepted);
+    }
+}
+
+#[cfg(test)]
+mod glr_test {
+    use crate::LRFSMType;
+
+    #[test]
+    fn ambiguous_test() {
+        let mut g = crate::Grammar::parse("E -> E + E | E * E | id").unwrap();
+        let table = g.to_lr_fsm(LRFSMType::SLR1).unwrap().to_parsing_table();
+        let tokens = ["id", "+", "id", "*", "id"];
+        let r = table.glr_parse(&tokens, 10);
+        assert!(r.accepted);
+        let root = &r.forest[r.root.unwrap()];
+        assert_eq!((root.symbol.as_str(), root.start, root.end), ("E", 0, 5));
+        assert_eq!(root.families.len(), 2);
+        assert_eq!(r.trees.len(), 2);
+        assert_ne!(r.trees[0], r.trees[1]);
+        for t in &r.trees {
+            assert_eq!(t.leaves(), tokens.to_vec());
+        }
+
+        assert!(!table.glr_parse(&["id", "+"], 10).accepted);
+    }
+
+    #[test]
+    fn same_trees_as_earley_test() {
+        let mut g = crate::Grammar::parse("S -> A S b | ϵ\nA -> a | ϵ").unwrap();
+        let tokens = ["a", "b", "b"];
+        let mut earley = g.earley_parse(&tokens, 10).unwrap().trees;
+        let mut glr = g
+            .to_lr_fsm(LRFSMType::LALR)
+            .unwrap()
+            .to_parsing_table()
+            .glr_parse(&tokens, 10)
+            .trees;
+        let key = |t: &crate::grammar::parse_tree::ParseTree| t.to_plaintext();
+        earley.sort_by_key(key);
+        glr.sort_by_key(key);
+        assert_eq!(earley, glr);
+    }
+
+    #[test]
+    fn cycle_test() {
+        let mut g = crate::Grammar::parse("S -> S | a").unwrap();
+        let r = g
+            .to_lr_fsm(LRFSMType::LALR)
+            .unwrap()
+            .to_parsing_table()
+            .glr_parse(&["a"], 10);
+        assert!(r.accepted);
+        assert_eq!(r.trees.len(), 1);
+    }
+
+    #[test]
+    fn long_ambiguous_test() {
+        let mut g = crate::Grammar::parse("E -> E + E | E * E | E | id").unwrap();
+        let table = g.to_lr_fsm(LRFSMType::SLR1).unwrap().to_parsing_table();
+        let tokens: Vec<&str> = std::iter::once("id")
+            .chain(["+", "id", "*", "id"].repeat(15))
+            .collect();
+        let now = std::time::Instant::now();
+        let r = table.glr_parse(&tokens, 10);
+        assert!(now.elapsed() < std::time::Duration::from_secs(5));
+        assert!(r.accepted);
+        assert_eq!(r.trees.len(), 10);
+        for t in &r.trees {
+            assert_eq!(t.leaves(), tokens);
+        }
+    }
+}
+
+#[cfg(test)]
+mod recovery_test {
+    use crate::grammar::ll1_parse::{LL1ParseAction, LL1Recovery};
+    use crate::grammar::lr_parse::{LRParseAction, LRRecovery};
+    use crate::{LRConflictPolicy, LRFSMType};
+
+    const EXPR: &str = "E -> E + T | T\nT -> T * F | F\nF -> ( E ) | id";
+
+    #[test]
+    fn ll1_panic_mode_test() {
+        let mut g = crate::Grammar::parse(EXPR).unwrap();
+        g.eliminate_left_recursion().unwrap();
+        let trace = g
+            .generate_ll1_parsing_table()
+            .parse_with_recovery(&["id", "+", "*", "id", ")"], 10);
+        assert!(!trace.accepted);
+        assert_eq!(trace.errors, 2);
+        assert!(
+            trace
+                .steps
+                .iter()
+                .any(|step| step.action
+                    == LL1ParseAction::Recover(LL1Recovery::Skip("*".to_string())))
+        );
+        assert_eq!(trace.steps.last().unwrap().action, LL1ParseAction::Accept);
+    }
+
+    #[test]
+    fn ll1_insert_test() {
+        let mut g = crate::Grammar::parse(EXPR).unwrap();
+        g.eliminate_left_recursion().unwrap();
+        let trace = g
+            .generate_ll1_parsing_table()
+            .parse_with_recovery(&["(", "id"], 10);
+        assert_eq!(trace.errors, 1);
+        assert!(trace.steps.iter().any(
+            |step| step.action == LL1ParseAction::Recover(LL1Recovery::Insert(")".to_string()))
+        ));
+        assert!(trace.to_parse_tree().is_err());
+    }
+
+    #[test]
+    fn lr_test() {
+        let mut g = crate::Grammar::parse(EXPR).unwrap();
+        let table = g.to_lr_fsm(LRFSMType::SLR1).unwrap().to_parsing_table();
+        let non_terminals: Vec<&str> = table.non_terminals.iter().map(|s| s.as_str()).collect();
+        let trace = table.parse_with_recovery(
+            &["(", "id", "+", "id"],
+            LRConflictPolicy::Refuse,
+            &non_terminals,
+            10,
+        );
+        assert_eq!(trace.errors, 1);
+        assert!(
+            trace
+                .steps
+                .iter()
+                .any(|step| step.action
+                    == LRParseAction::Recover(LRRecovery::Insert(")".to_string())))
+        );
+        assert_eq!(trace.steps.last().unwrap().action, LRParseAction::Accept);
+
+        let trace = table.parse_with_recovery(
+            &["id", ")", "id"],
+            LRConflictPolicy::Refuse,
+            &non_terminals,
+            10,
+        );
+        assert_eq!(trace.errors, 1);
+        assert!(trace
+            .steps
+            .iter()
+            .any(|step| matches!(step.action, LRParseAction::Recover(LRRecovery::Pop(..)))));
+        assert_eq!(trace.steps.last().unwrap().action, LRParseAction::Accept);
+    }
+
+    #[test]
+    fn lr_insert_test() {
+        let mut g = crate::Grammar::parse(EXPR).unwrap();
+        let table = g.to_lr_fsm(LRFSMType::SLR1).unwrap().to_parsing_table();
+        // I0 expects both ( and id, only shifting id leads to a state expecting +
+        let trace = table.parse_with_recovery(&["+", "id"], LRConflictPolicy::Refuse, &[], 10);
+        assert_eq!(
+            trace.steps[0].action,
+            LRParseAction::Recover(LRRecovery::Insert("id".to_string()))
+        );
+        assert_eq!(trace.errors, 1);
+        assert_eq!(trace.steps.last().unwrap().action, LRParseAction::Accept);
+    }
+
+    #[test]
+    fn error_non_terminal_test() {
+        use crate::{grammar_to_output_with_options, Format, Output, ParseOptions};
+
+        let outputs = [Output::LRParse(LRFSMType::SLR1, Format::Plain)];
+        let parse = |error_non_terminals| {
+            let options = ParseOptions {
+                input: Some("id ) id"),
+                recovery: true,
+                error_non_terminals,
+            };
+            grammar_to_output_with_options(EXPR, &[], &outputs, &options)
+                .unwrap()
+                .remove(0)
+        };
+        let trace = parse(&["F"]).unwrap();
+        assert!(trace.contains("push F"));
+        assert!(!trace.contains("push E") && !trace.contains("push T"));
+        // all of them by default, E is tried first
+        assert!(parse(&[]).unwrap().contains("push E"));
+        assert_eq!(parse(&["X"]), Err("X is not a non-terminal".to_string()));
+    }
+
+    #[test]
+    fn limit_test() {
+        let mut g = crate::Grammar::parse(EXPR).unwrap();
+        g.eliminate_left_recursion().unwrap();
+        let trace = g
+            .generate_ll1_parsing_table()
+            .parse_with_recovery(&["id", "id", "id", "id"], 2);
+        assert_eq!(trace.errors, 2);
+        assert!(matches!(
+            trace.steps.last().unwrap().action,
+            LL1ParseAction::Error(_)
+        ));
+    }
+}
+
+#[cfg(test)]
+mod lr_fsm_dot_test {
+    use crate::LRFSMType;
+
+    #[test]
+    fn record_test() {
+        let mut g = crate::Grammar::parse("S -> a S b | ϵ").unwrap();
+        let dot = g.to_lr_fsm(LRFSMType::LR0).unwrap().to_dot();
+        assert!(dot.contains("  I0 [label=\"{I0|S' -\\> .S\\l|S -\\> .a S b\\lS -\\> ϵ.\\l}\"];"));
+        assert!(dot.contains("  I0 -> I2 [label=\"a\"];"));
+        assert!(dot.contains("  I1 -> accept [label=\"$\"];"));
+    }
+}
+
+#[cfg(test)]
+mod mermaid_plantuml_test {
+    use crate::{LRConflictPolicy, LRFSMType};
+
+    #[test]
+    fn lr_fsm_test() {
+        let mut g = crate::Grammar::parse("S -> a S b | ϵ").unwrap();
+        let fsm = g.to_lr_fsm(LRFSMType::LR0).unwrap();
+        let mermaid = fsm.to_mermaid();
+        assert!(mermaid.starts_with("stateDiagram-v2\n    I0 : S' -> .S\n"));
+        assert!(mermaid.contains("\n    I2 --> I2 : a\n"));
+        assert!(mermaid.ends_with("\n    I1 --> [*] : $"));
+        let plantuml = fsm.to_plantuml();
+        assert!(plantuml
+            .contains("\nI0 : S' <U+002D><U+003E> .S\nI0 : --\nI0 : S <U+002D><U+003E> .a S b\n"));
+        assert!(plantuml.ends_with("\nI1 --> [*] : <U+0024>\n@enduml"));
+    }
+
+    #[test]
+    fn plantuml_escape_test() {
+        let mut g = crate::Grammar::parse("S -> \" S \" | a").unwrap();
+        let plantuml = g.to_lr_fsm(LRFSMType::LR0).unwrap().to_plantuml();
+        assert!(plantuml.contains("\nI0 --> I1 : <U+0022>\n"));
+        assert!(!plantuml.lines().any(|l| l.contains('"') || l.contains('|')));
+
+        let tree = g
+            .to_lr_fsm(LRFSMType::LR0)
+            .unwrap()
+            .to_parsing_table()
+            .parse(&["\"", "a", "\""], LRConflictPolicy::Refuse)
+            .to_parse_tree()
+            .unwrap();
+        assert_eq!(
+            tree.to_plantuml(),
+            "@startwbs\n* S\n**_ <U+0022>\n** S\n***_ a\n**_ <U+0022>\n@endwbs"
+        );
+    }
+
+    #[test]
+    fn parse_tree_test() {
+        let mut g = crate::Grammar::parse("S -> a S b | ϵ").unwrap();
+        let tree = g
+            .to_lr_fsm(LRFSMType::LALR)
+            .unwrap()
+            .to_parsing_table()
+            .parse(&["a", "b"], LRConflictPolicy::Refuse)
+            .to_parse_tree()
+            .unwrap();
+        assert_eq!(
+            tree.to_plantuml(),
+            "@startwbs\n* S\n**_ a\n** S\n***_ ϵ\n**_ b\n@endwbs"
+        );
+        assert_eq!(
+            tree.to_mermaid(),
+            "flowchart TD\n    n0(\"S\")\n    n1[\"a\"]\n    n0 --> n1\n    n2(\"S\")\n    \
+             n3(\"ϵ\")\n    n2 --> n3\n    n0 --> n2\n    n4[\"b\"]\n    n0 --> n4"
+        );
+    }
+}
+
+#[cfg(test)]
+mod markdown_test {
+    use crate::LRFSMType;
+
+    const DANGLING_ELSE: &str = "S -> i E t S | i E t S e S | a\nE -> b";
+
+    #[test]
+    fn production_test() {
+        let g = crate::Grammar::parse(DANGLING_ELSE).unwrap();
+        assert_eq!(
+            g.to_production_output_vec().to_markdown(),
+            "| Left | Right |\n\
+             | --- | --- |\n\
+             | S | i E t S \\| i E t S e S \\| a |\n\
+             | E | b |"
+        );
+    }
+
+    #[test]
+    fn conflict_test() {
+        let mut g = crate::Grammar::parse(DANGLING_ELSE).unwrap();
+        let ll1 = g.generate_ll1_parsing_table().to_markdown();
+        assert!(ll1.contains("| S |  | **S -> i E t S \\| i E t S e S** |"));
+        let lr0 = g
+            .to_lr_fsm(LRFSMType::LR0)
+            .unwrap()
+            .to_parsing_table()
+            .to_markdown();
+        assert!(lr0.contains("| **r(S -> i E t S); s8** |"));
+    }
+
+    #[test]
+    fn lr_fsm_test() {
+        let mut g = crate::Grammar::parse("S -> a").unwrap();
+        assert_eq!(
+            g.to_lr_fsm(LRFSMType::LR0).unwrap().to_markdown(),
+            "**I0**\n\n- `S' -> .S`\n\nclosure:\n\n- `S -> .a`\n\n\
+             goto:\n\n- `S` → I1\n- `a` → I2\n\n\
+             **I1**\n\n- `S' -> S.`\n\ngoto:\n\n- `$` → accept\n\n\
+             **I2**\n\n- `S -> a.`"
+        );
+    }
+}
+
+#[cfg(test)]
+mod html_test {
+    use crate::LRFSMType;
+
+    #[test]
+    fn escape_test() {
+        let mut g = crate::Grammar::parse("S -> < S > | ϵ").unwrap();
+        let html = g.to_production_output_vec().to_html();
+        assert!(html.contains("<tr><td>S</td><td>&lt; S &gt; | ϵ</td></tr>"));
+        let html = g.to_lr_fsm(LRFSMType::LR0).unwrap().to_html();
+        assert!(html.contains("<td>S' -&gt; S.</td><td></td><td>$ → accept</td>"));
+    }
+
+    #[test]
+    fn conflict_test() {
+        let mut g = crate::Grammar::parse("S -> i E t S | i E t S e S | a\nE -> b").unwrap();
+        let html = g
+            .to_lr_fsm(LRFSMType::SLR1)
+            .unwrap()
+            .to_parsing_table()
+            .to_html();
+        assert!(html.contains("<span class=\"conflict\">r(S -&gt; i E t S)<br>s8</span>"));
+    }
+
+    #[test]
+    fn report_test() {
+        let html = crate::grammar_to_html_report("E -> E + T | T\nT -> id", &[]).unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert_eq!(html.matches("<h2>").count(), 11);
+        // left recursion makes the LL(1) table conflict
+        assert!(html.contains("class=\"conflict\""));
+        assert!(html.ends_with("</html>"));
+    }
+}
+
+#[cfg(test)]
+mod latex_document_test {
+    use crate::{
+        grammar_to_output_with_options, latex_document, Format, LRFSMType, Output, ParseOptions,
+    };
+
+    #[test]
+    fn document_test() {
+        let options = ParseOptions {
+            input: Some("a"),
+            ..Default::default()
+        };
+        let bodies: Vec<String> = grammar_to_output_with_options(
+            "S -> a S | ϵ",
+            &[],
+            &[
+                Output::LRFSM(LRFSMType::LR0, Format::LaTeX),
+                Output::LL1ParseTree(Format::LaTeX),
+            ],
+            &options,
+        )
+        .unwrap()
+        .into_iter()
+        .map(|r| r.unwrap())
+        .collect();
+        let doc = latex_document(&bodies);
+        assert!(doc.starts_with("\\documentclass{article}\n"));
+        assert!(doc.contains("\\usetikzlibrary{automata"));
+        assert!(doc.contains("\\begin{document}\n\\begin{tikzpicture}"));
+        assert!(doc.contains("\\end{tikzpicture}\n\n\\begin{forest}"));
+        assert!(doc.ends_with("\\end{forest}\n\\end{document}"));
+    }
+}
+
+#[cfg(test)]
+mod lr_fsm_layout_test {
+    use crate::LRFSMType;
+
+    #[test]
+    fn layered_test() {
+        let mut g = crate::Grammar::parse("S -> a S | b").unwrap();
+        let latex = g.to_lr_fsm(LRFSMType::LR0).unwrap().to_latex();
+        // I1, I2 and I3 form the second layer, I4 and accept the third
+        assert!(latex.contains("\\node [block] (I_0) at (0.00, 0.00)\n"));
+        for i in 1..=3 {
+            assert!(latex.contains(&format!("(I_{}) at (5.00, ", i)));
+        }
+        assert!(latex.contains("(I_4) at (10.00, "));
+        assert!(latex.contains("\\node (accept) at (10.00, "));
+        assert!(latex.contains("\\path [->] (I_2) edge [loop above] node [above]{a} (I_2);"));
+        assert!(latex.contains("\\path [->] (I_2) edge [bend left=20] node [auto]{b} (I_3);"));
+    }
+
+    #[test]
+    fn back_edge_test() {
+        let mut g = crate::Grammar::parse("S -> i S e S | a").unwrap();
+        let latex = g.to_lr_fsm(LRFSMType::LR0).unwrap().to_latex();
+        assert!(latex.contains("edge [bend left=30] node [auto]{i}"));
+    }
+}
+
+#[cfg(test)]
+mod typst_test {
+    use crate::{grammar_to_output, Format, LRFSMType, Output};
+
+    #[test]
+    fn escape_test() {
+        let g = crate::Grammar::parse("S -> \" S \\ | a").unwrap();
+        let typst = g.to_production_output_vec().to_typst();
+        assert!(typst.contains("  \"S\", \"\\\" S \\\\ | a\","));
+    }
+
+    #[test]
+    fn conflict_test() {
+        let mut g = crate::Grammar::parse("S -> i E t S | i E t S e S | a\nE -> b").unwrap();
+        let typst = g
+            .to_lr_fsm(LRFSMType::SLR1)
+            .unwrap()
+            .to_parsing_table()
+            .to_typst();
+        assert!(typst.starts_with("#table(\n  columns: 9,"));
+        assert!(typst.contains("text(fill: red, \"r(S -> i E t S); s8\")"));
+        let typst = g.generate_ll1_parsing_table().to_typst();
+        assert!(typst.contains("text(fill: red, \"S -> i E t S | i E t S e S\")"));
+    }
+
+    #[test]
+    fn fsm_test() {
+        let output = grammar_to_output(
+            "S -> a S | b",
+            &[],
+            &[Output::LRFSM(LRFSMType::LR0, Format::Typst)],
+        )
+        .unwrap();
+        let typst = output[0].as_ref().unwrap();
+        assert!(typst.starts_with("#import \"@preview/fletcher:"));
+        assert!(typst.contains("  node((0, 0), align(left)[*I0* \\ #\"S' -> .S\""));
+        assert!(typst.contains("[accept], stroke: none"));
+        // I2 -> I2 on a
+        assert!(typst.contains("\"->\", [#\"a\"], bend: "));
+        assert!(typst.ends_with(")"));
+    }
 }
@@ -1,24 +1,81 @@
-use compiler_course_helper::{grammar_to_output, Action, Format, LRFSMType, Output};
+use compiler_course_helper::{
+    grammar_to_html_report, grammar_to_output_with_options, latex_document, Action, Format,
+    LRFSMType, Output, ParseOptions,
+};
 use std::{collections::HashMap, fs, io::BufRead};
 
 fn print_help() {
     println!("Usage: compiler-course-helper [action]... output... [option] [grammar file]");
+    println!("       compiler-course-helper [action]... report [grammar file]");
+    println!("report: One HTML page with the productions, nullable first and follow,");
+    println!("        the LL(1) table and every LR automaton and table");
     println!("action:");
     println!("  elf: Eliminate left recursion");
+    println!("  lf: Left factoring");
+    println!("  eep: Eliminate ϵ-productions");
+    println!("  eup: Eliminate unit productions and cycles");
+    println!("  rus: Remove useless symbols");
+    println!("  cnf: Convert to Chomsky normal form");
+    println!("  gnf: Convert to Greibach normal form");
     println!("output:");
     println!("  prod: Productions");
     println!("  nff: Nullable first and follow");
     println!("  ll1: LL(1) parsing table");
     println!("  lr0fsm: LR(0) Automata");
+    println!("  slr1fsm: SLR(1) Automata");
     println!("  lr1fsm: LR(1) Automata");
     println!("  lalrfsm: LALR Automata");
     println!("  lr0table: LR(0) parsing table");
+    println!("  slr1table: SLR(1) parsing table");
     println!("  lr1table: LR(1) parsing table");
     println!("  lalrtable: LALR parsing table");
+    println!("  useless: Non-productive and unreachable symbols");
+    println!("  unit: Unit productions inlined by eup and unit cycles");
+    println!("  ll1conflicts: LL(1) parsing table conflicts");
+    println!("  lr0conflicts: LR(0) parsing table conflicts");
+    println!("  slr1conflicts: SLR(1) parsing table conflicts");
+    println!("  lr1conflicts: LR(1) parsing table conflicts");
+    println!("  lalrconflicts: LALR parsing table conflicts");
+    println!("  classify: LL(1), LR(0), SLR(1), LALR(1) and LR(1) membership");
+    println!("  ll1parse: LL(1) parsing steps of the input string");
+    println!("  lr0parse: LR(0) parsing steps of the input string");
+    println!("  slr1parse: SLR(1) parsing steps of the input string");
+    println!("  lr1parse: LR(1) parsing steps of the input string");
+    println!("  lalrparse: LALR parsing steps of the input string");
+    println!("  ll1tree: LL(1) parse tree of the input string");
+    println!("  lr0tree: LR(0) parse tree of the input string");
+    println!("  slr1tree: SLR(1) parse tree of the input string");
+    println!("  lr1tree: LR(1) parse tree of the input string");
+    println!("  lalrtree: LALR parse tree of the input string");
+    println!("  ll1deriv: Leftmost derivation of the input string by LL(1) parsing");
+    println!("  lr0deriv: Rightmost derivation of the input string by LR(0) parsing");
+    println!("  slr1deriv: Rightmost derivation of the input string by SLR(1) parsing");
+    println!("  lr1deriv: Rightmost derivation of the input string by LR(1) parsing");
+    println!("  lalrderiv: Rightmost derivation of the input string by LALR parsing");
+    println!("  earley: Earley chart and parse trees (at most 10) of the input string");
+    println!("  cyk: CYK table of the input string on the Chomsky normal form");
+    println!("  lr0glr: GLR parse forest and trees (at most 10) over the LR(0) table");
+    println!("  slr1glr: GLR parse forest and trees (at most 10) over the SLR(1) table");
+    println!("  lr1glr: GLR parse forest and trees (at most 10) over the LR(1) table");
+    println!("  lalrglr: GLR parse forest and trees (at most 10) over the LALR table");
     println!("option:");
     println!("  -h: Print this help");
     println!("  -l: Print in LaTeX format");
+    println!("  -s: Print in LaTeX format as one standalone document for pdflatex");
     println!("  -j: Print in JSON format");
+    println!("  -d: Print in Graphviz DOT format (LR automata, parse trees and forests only)");
+    println!("  -m: Print in Mermaid format (LR automata and parse trees only)");
+    println!("  -p: Print in PlantUML format (LR automata and parse trees only)");
+    println!(
+        "  -k: Print in Markdown format (productions, symbols, automata and parsing tables only)"
+    );
+    println!("  -H: Print in HTML format (productions, symbols, automata and parsing tables only)");
+    println!(
+        "  -t: Print in Typst format (productions, symbols, automata and parsing tables only)"
+    );
+    println!("  -i <input>: Input string for parsing, tokens separated by spaces");
+    println!("  -r: Recover from syntax errors in parsing steps (panic mode and phrase level)");
+    println!("  -e <non-terminals>: Error non-terminals for LR panic mode, separated by spaces (default: all)");
 }
 
 fn main() {
@@ -26,21 +83,34 @@ fn main() {
     let mut outputs: Vec<Output> = Vec::new();
     let args = std::env::args().skip(1).collect::<Vec<String>>();
 
-    let action_map: HashMap<&str, Action> = [("elf", Action::EliminateLeftRecursion)]
-        .iter()
-        .cloned()
-        .collect();
+    let action_map: HashMap<&str, Action> = [
+        ("elf", Action::EliminateLeftRecursion),
+        ("lf", Action::LeftFactor),
+        ("eep", Action::EliminateEpsilonProductions),
+        ("eup", Action::EliminateUnitProductions),
+        ("rus", Action::RemoveUselessSymbols),
+        ("cnf", Action::ChomskyNormalForm),
+        ("gnf", Action::GreibachNormalForm),
+    ]
+    .iter()
+    .cloned()
+    .collect();
     let output_map: HashMap<&str, Output> = [
         ("prod", Output::Production(Format::Plain)),
         ("nff", Output::NonTerminal(Format::Plain)),
         ("ll1", Output::LL1ParsingTable(Format::Plain)),
         ("lr0fsm", Output::LRFSM(LRFSMType::LR0, Format::Plain)),
+        ("slr1fsm", Output::LRFSM(LRFSMType::SLR1, Format::Plain)),
         ("lr1fsm", Output::LRFSM(LRFSMType::LR1, Format::Plain)),
         ("lalrfsm", Output::LRFSM(LRFSMType::LALR, Format::Plain)),
         (
             "lr0table",
             Output::LRParsingTable(LRFSMType::LR0, Format::Plain),
         ),
+        (
+            "slr1table",
+            Output::LRParsingTable(LRFSMType::SLR1, Format::Plain),
+        ),
         (
             "lr1table",
             Output::LRParsingTable(LRFSMType::LR1, Format::Plain),
@@ -49,6 +119,71 @@ fn main() {
             "lalrtable",
             Output::LRParsingTable(LRFSMType::LALR, Format::Plain),
         ),
+        ("useless", Output::UselessSymbols(Format::Plain)),
+        ("unit", Output::UnitProductions(Format::Plain)),
+        ("ll1conflicts", Output::LL1Conflicts(Format::Plain)),
+        (
+            "lr0conflicts",
+            Output::Conflicts(LRFSMType::LR0, Format::Plain),
+        ),
+        (
+            "slr1conflicts",
+            Output::Conflicts(LRFSMType::SLR1, Format::Plain),
+        ),
+        (
+            "lr1conflicts",
+            Output::Conflicts(LRFSMType::LR1, Format::Plain),
+        ),
+        (
+            "lalrconflicts",
+            Output::Conflicts(LRFSMType::LALR, Format::Plain),
+        ),
+        ("classify", Output::Classification(Format::Plain)),
+        ("ll1parse", Output::LL1Parse(Format::Plain)),
+        ("lr0parse", Output::LRParse(LRFSMType::LR0, Format::Plain)),
+        ("slr1parse", Output::LRParse(LRFSMType::SLR1, Format::Plain)),
+        ("lr1parse", Output::LRParse(LRFSMType::LR1, Format::Plain)),
+        ("lalrparse", Output::LRParse(LRFSMType::LALR, Format::Plain)),
+        ("ll1tree", Output::LL1ParseTree(Format::Plain)),
+        (
+            "lr0tree",
+            Output::LRParseTree(LRFSMType::LR0, Format::Plain),
+        ),
+        (
+            "slr1tree",
+            Output::LRParseTree(LRFSMType::SLR1, Format::Plain),
+        ),
+        (
+            "lr1tree",
+            Output::LRParseTree(LRFSMType::LR1, Format::Plain),
+        ),
+        (
+            "lalrtree",
+            Output::LRParseTree(LRFSMType::LALR, Format::Plain),
+        ),
+        ("ll1deriv", Output::LeftmostDerivation(Format::Plain)),
+        (
+            "lr0deriv",
+            Output::RightmostDerivation(LRFSMType::LR0, Format::Plain),
+        ),
+        (
+            "slr1deriv",
+            Output::RightmostDerivation(LRFSMType::SLR1, Format::Plain),
+        ),
+        (
+            "lr1deriv",
+            Output::RightmostDerivation(LRFSMType::LR1, Format::Plain),
+        ),
+        (
+            "lalrderiv",
+            Output::RightmostDerivation(LRFSMType::LALR, Format::Plain),
+        ),
+        ("earley", Output::Earley(Format::Plain)),
+        ("cyk", Output::CYK(Format::Plain)),
+        ("lr0glr", Output::GLR(LRFSMType::LR0, Format::Plain)),
+        ("slr1glr", Output::GLR(LRFSMType::SLR1, Format::Plain)),
+        ("lr1glr", Output::GLR(LRFSMType::LR1, Format::Plain)),
+        ("lalrglr", Output::GLR(LRFSMType::LALR, Format::Plain)),
     ]
     .iter()
     .cloned()
@@ -65,15 +200,64 @@ fn main() {
         i += 1;
     }
 
+    let report = outputs.is_empty() && args.get(i).map(|s| s.as_str()) == Some("report");
+    if report {
+        i += 1;
+    }
+
     let mut output_format = Format::Plain;
-    while i < args.len() && ["-h", "--help", "-l", "-j"].contains(&args[i].as_str()) {
+    let mut input: Option<String> = None;
+    let mut recovery = false;
+    let mut standalone = false;
+    let mut error_non_terminals: Option<String> = None;
+    while i < args.len()
+        && [
+            "-h", "--help", "-l", "-s", "-j", "-d", "-m", "-p", "-k", "-H", "-t", "-i", "-r", "-e",
+        ]
+        .contains(&args[i].as_str())
+    {
         if args[i] == "-h" || args[i] == "--help" {
             print_help();
             return;
         } else if args[i] == "-l" {
             output_format = Format::LaTeX;
+        } else if args[i] == "-s" {
+            output_format = Format::LaTeX;
+            standalone = true;
         } else if args[i] == "-j" {
             output_format = Format::JSON;
+        } else if args[i] == "-d" {
+            output_format = Format::Dot;
+        } else if args[i] == "-m" {
+            output_format = Format::Mermaid;
+        } else if args[i] == "-p" {
+            output_format = Format::PlantUML;
+        } else if args[i] == "-k" {
+            output_format = Format::Markdown;
+        } else if args[i] == "-H" {
+            output_format = Format::Html;
+        } else if args[i] == "-t" {
+            output_format = Format::Typst;
+        } else if args[i] == "-r" {
+            recovery = true;
+        } else if args[i] == "-i" {
+            i += 1;
+            match args.get(i) {
+                Some(s) => input = Some(s.clone()),
+                None => {
+                    print_help();
+                    return;
+                }
+            }
+        } else if args[i] == "-e" {
+            i += 1;
+            match args.get(i) {
+                Some(s) => error_non_terminals = Some(s.clone()),
+                None => {
+                    print_help();
+                    return;
+                }
+            }
         }
         i += 1;
     }
@@ -85,7 +269,7 @@ fn main() {
         })
         .collect();
 
-    if i + 1 < args.len() || outputs.len() < 1 {
+    if i + 1 < args.len() || (outputs.is_empty() && !report) {
         print_help();
         return;
     }
@@ -101,7 +285,34 @@ fn main() {
         fs::read_to_string(args[i].as_str()).expect("Failed to read file")
     };
 
-    match grammar_to_output(&grammar, &actions, &outputs) {
+    if report {
+        match grammar_to_html_report(&grammar, &actions) {
+            Ok(html) => println!("{}", html),
+            Err(e) => println!("ERROR! {}", e),
+        }
+        return;
+    }
+
+    let error_non_terminals: Vec<&str> = error_non_terminals
+        .as_deref()
+        .map_or(Vec::new(), |s| s.split_whitespace().collect());
+    let options = ParseOptions {
+        input: input.as_deref(),
+        recovery,
+        error_non_terminals: &error_non_terminals,
+    };
+    match grammar_to_output_with_options(&grammar, &actions, &outputs, &options) {
+        Ok(v) if standalone => {
+            let bodies: Vec<String> = v
+                .into_iter()
+                .enumerate()
+                .map(|(i, e)| match e {
+                    Ok(o) => o,
+                    Err(e) => format!("% Error {}-th output: {}", i, e),
+                })
+                .collect();
+            println!("{}", latex_document(&bodies));
+        }
         Ok(v) => {
             for (i, e) in v.into_iter().enumerate() {
                 match e {